@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::words::VALID_WORDS;
+
+// The days the answer isn't just the next word in VALID_WORDS
+const SCHEDULE: &str = include_str!("answers.txt");
+
+// Wordle 506 (2022-11-07) was the first one the NYT's editor picked, the word
+// list says nothing about it or anything after
+const EDITOR_PICKS_FROM: u32 = 506;
+
+// A day's answer, and whether anyone's checked it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Answer<'a> {
+    // From a schedule file
+    Verified(&'a str),
+    // Just the word at that day in VALID_WORDS, which the game followed until
+    // the editor took over, apart from the days it changed
+    Guess(&'a str),
+}
+
+impl<'a> Answer<'a> {
+    pub fn word(self) -> &'a str {
+        match self {
+            Answer::Verified(word) | Answer::Guess(word) => word,
+        }
+    }
+}
+
+// Wordle answers by day number. The original word list is in the order the
+// answers went out, anything that's changed since comes from schedule files
+// with a `<day> <answer>` per line.
+#[derive(Debug)]
+pub struct AnswerList {
+    answers: HashMap<u32, String>,
+}
+
+impl AnswerList {
+    pub fn bundled() -> AnswerList {
+        AnswerList::parse(SCHEDULE).expect("bundled answers.txt should parse")
+    }
+
+    // The bundled schedule with another file's answers on top
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<AnswerList> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("couldn't read answers from {:?}: {}", path, e))?;
+        let mut answers = AnswerList::bundled();
+        answers
+            .answers
+            .extend(AnswerList::parse(&contents)?.answers);
+        Ok(answers)
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<AnswerList> {
+        let mut answers = HashMap::new();
+        for line in contents.lines().map(str::trim) {
+            // Blank lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad_line =
+                || anyhow::anyhow!("answers need to look like <day> <answer>, got {}", line);
+            let (day, answer) = line.split_once(char::is_whitespace).ok_or_else(bad_line)?;
+            let answer = answer.trim();
+            if answer.len() != 5 || !answer.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(bad_line());
+            }
+            answers.insert(day.parse().map_err(|_| bad_line())?, answer.to_lowercase());
+        }
+        Ok(AnswerList { answers })
+    }
+
+    // Nothing for days since the editor took over that no schedule has
+    pub fn answer_for_day(&self, day: u32) -> Option<Answer<'_>> {
+        match self.answers.get(&day) {
+            Some(answer) => Some(Answer::Verified(answer)),
+            None if day < EDITOR_PICKS_FROM => {
+                VALID_WORDS.get(day as usize).copied().map(Answer::Guess)
+            }
+            None => None,
+        }
+    }
+}
+
+// Wordle 0 came out on 2021-06-19 and there's been one a day since
+pub fn day_for_date(date: NaiveDate) -> Option<u32> {
+    let first = NaiveDate::from_ymd_opt(2021, 6, 19)?;
+    u32::try_from((date - first).num_days()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{day_for_date, Answer, AnswerList};
+
+    #[test]
+    fn test_parse() {
+        let answers = AnswerList::parse(
+            "# day answer
+228 SHAKE
+229 abide
+
+230 sassy",
+        )
+        .unwrap();
+        assert_eq!(answers.answer_for_day(228), Some(Answer::Verified("shake")));
+        assert_eq!(answers.answer_for_day(230), Some(Answer::Verified("sassy")));
+        // Everything else is a guess from the word list, until the editor
+        // took over
+        assert_eq!(answers.answer_for_day(0), Some(Answer::Guess("cigar")));
+        assert_eq!(answers.answer_for_day(231), Some(Answer::Guess("aloft")));
+        assert_eq!(answers.answer_for_day(505), Some(Answer::Guess("inept")));
+        assert_eq!(answers.answer_for_day(1950), None);
+        assert_eq!(answers.answer_for_day(100_000), None);
+        assert!(AnswerList::parse("229").is_err());
+        assert!(AnswerList::parse("229 abides").is_err());
+        assert!(AnswerList::parse("day abide").is_err());
+    }
+
+    #[test]
+    fn test_bundled() {
+        let answers = AnswerList::bundled();
+        assert_eq!(answers.answer_for_day(1), Some(Answer::Guess("rebut")));
+        // Swapped out the day it was due
+        assert_eq!(answers.answer_for_day(324), Some(Answer::Verified("shine")));
+    }
+
+    #[test]
+    fn test_day_for_date() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(day_for_date(date(2021, 6, 19)), Some(0));
+        assert_eq!(day_for_date(date(2022, 2, 3)), Some(229));
+        assert_eq!(day_for_date(date(2021, 6, 18)), None);
+    }
+}
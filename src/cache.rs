@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serenity::model::id::{ChannelId, GuildId};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::platform::Thread;
+use crate::threads::parse_solvers_thread_name;
+
+// The solvers threads we know about by guild, game and day, so a result
+// doesn't need a trip to discord to find its thread. Filled in from each
+// guild's active threads when we connect, then kept up to date as threads are
+// made and deleted.
+//
+// Finding or making a guild's thread happens under that guild's lock, so two
+// people posting at once can't both make one.
+#[derive(Default)]
+pub struct ThreadCache {
+    threads: Mutex<HashMap<(GuildId, String, u32), Thread>>,
+    locks: Mutex<HashMap<GuildId, Arc<AsyncMutex<()>>>>,
+}
+
+impl ThreadCache {
+    // Held while finding or making one of the guild's threads
+    pub async fn lock(&self, guild_id: GuildId) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            locks.entry(guild_id).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    pub fn get(&self, guild_id: GuildId, name: &str) -> Option<Thread> {
+        let (game, day) = parse_solvers_thread_name(name)?;
+        let threads = self.threads.lock().unwrap();
+        threads.get(&(guild_id, game.to_string(), day)).cloned()
+    }
+
+    pub fn is_thread(&self, channel_id: ChannelId) -> bool {
+        let threads = self.threads.lock().unwrap();
+        threads.values().any(|thread| thread.id == channel_id)
+    }
+
+    // Every thread we know about for the game, with its day
+    pub fn game_threads(&self, guild_id: GuildId, game: &str) -> Vec<(u32, Thread)> {
+        let threads = self.threads.lock().unwrap();
+        threads
+            .iter()
+            .filter(|((guild, name, _), _)| *guild == guild_id && name == game)
+            .map(|((_, _, day), thread)| (*day, thread.clone()))
+            .collect()
+    }
+
+    // Anything that isn't a solvers thread is left out
+    pub fn insert(&self, thread: Thread) {
+        if let Some((game, day)) = parse_solvers_thread_name(&thread.name) {
+            let key = (thread.guild_id, game.to_string(), day);
+            self.threads.lock().unwrap().insert(key, thread);
+        }
+    }
+
+    pub fn remove(&self, thread_id: ChannelId) {
+        let mut threads = self.threads.lock().unwrap();
+        threads.retain(|_, thread| thread.id != thread_id);
+    }
+
+    // Swap what we know about the guild's threads for what discord says
+    pub fn reconcile(&self, guild_id: GuildId, threads: Vec<Thread>) {
+        self.threads
+            .lock()
+            .unwrap()
+            .retain(|(guild, _, _), _| *guild != guild_id);
+        for thread in threads {
+            self.insert(thread);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::{
+        channel::ChannelType,
+        id::{ChannelId, GuildId},
+    };
+
+    use super::ThreadCache;
+    use crate::platform::Thread;
+
+    fn thread(id: u64, guild_id: u64, name: &str) -> Thread {
+        Thread {
+            id: ChannelId(id),
+            guild_id: GuildId(guild_id),
+            parent_id: Some(ChannelId(10)),
+            name: name.to_string(),
+            kind: ChannelType::PublicThread,
+            archived: false,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn test_thread_cache() {
+        let cache = ThreadCache::default();
+        cache.insert(thread(1, 1, "Wordle Solvers 231"));
+        cache.insert(thread(2, 2, "Wordle Solvers 231"));
+        cache.insert(thread(3, 1, "general chat"));
+        assert_eq!(
+            cache.get(GuildId(1), "Wordle Solvers 231"),
+            Some(thread(1, 1, "Wordle Solvers 231"))
+        );
+        assert_eq!(cache.get(GuildId(1), "Heardle Solvers 231"), None);
+        assert_eq!(cache.get(GuildId(1), "general chat"), None);
+        assert!(cache.is_thread(ChannelId(2)));
+        assert!(!cache.is_thread(ChannelId(3)));
+
+        cache.remove(ChannelId(1));
+        assert_eq!(cache.get(GuildId(1), "Wordle Solvers 231"), None);
+
+        // Only the reconciled guild's threads get replaced
+        cache.insert(thread(4, 1, "Wordle Solvers 230"));
+        cache.reconcile(GuildId(1), vec![thread(5, 1, "Heardle Solvers 16")]);
+        assert_eq!(cache.get(GuildId(1), "Wordle Solvers 230"), None);
+        assert!(cache.get(GuildId(1), "Heardle Solvers 16").is_some());
+        assert!(cache.get(GuildId(2), "Wordle Solvers 231").is_some());
+        assert_eq!(
+            cache.game_threads(GuildId(1), "Heardle"),
+            vec![(16, thread(5, 1, "Heardle Solvers 16"))]
+        );
+        assert!(cache.game_threads(GuildId(1), "Wordle").is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::answers::{day_for_date, AnswerList};
+use crate::detector::{
+    calculate_guess_scores, calculate_pattern_possibilities, calculate_word_possibilities,
+    parse_words_list, summarize_guess_scores, wordle_word_to_char_array,
+};
+use crate::games::{game_or_default, parse_day, GAMES};
+use crate::grid::{detect_theme, parse_grid, row_to_emoji};
+use crate::stats::{
+    calculate_leaderboard, calculate_stats, format_hard_mode, format_leaderboard, format_stats,
+    Window,
+};
+use crate::store::GameResult;
+
+pub const DEFAULT_GAME: &str = "Wordle";
+
+// What the bot says back to a command, however the command came in.
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    Text(String),
+    Embed {
+        title: String,
+        description: String,
+        footer: String,
+    },
+}
+
+// Words in a reply that's going somewhere public get wrapped in spoiler tags.
+fn word_tag(word: &str, spoilers: bool) -> String {
+    if spoilers {
+        format!("||`{}`||", word)
+    } else {
+        format!("`{}`", word)
+    }
+}
+
+// A wordle number like "229" or "#229" standing in for the answer
+fn parse_wordle_number(text: &str) -> Option<u32> {
+    parse_day(text.trim().trim_start_matches('#'))
+}
+
+fn scheduled_answer(answers: &AnswerList, day: u32) -> Result<String, String> {
+    answers
+        .answer_for_day(day)
+        .map(|answer| answer.word().to_string())
+        .ok_or_else(|| format!("Weird, I don't know the answer to wordle {} yet.", day))
+}
+
+// Guesses can start with the wordle number instead of ending with the answer,
+// then the answer comes from the schedule.
+fn fill_in_answer(query: &str, answers: &AnswerList) -> Result<String, String> {
+    let (first, guesses) = query.split_once('\n').unwrap_or((query, ""));
+    let day = match parse_wordle_number(first) {
+        Some(day) => day,
+        None => return Ok(query.to_string()),
+    };
+    let answer = scheduled_answer(answers, day)?;
+    let mut rows: Vec<&str> = guesses
+        .lines()
+        .map(str::trim)
+        .filter(|row| !row.is_empty())
+        .collect();
+    if !rows
+        .last()
+        .is_some_and(|row| row.eq_ignore_ascii_case(&answer))
+    {
+        rows.push(&answer);
+    }
+    Ok(rows.join("\n"))
+}
+
+// Guesses one per line, answer last. Or the wordle number first and the
+// answer left off.
+pub fn wordle_stats(
+    query: &str,
+    spoilers: bool,
+    answers: &AnswerList,
+    words_to_show: usize,
+) -> String {
+    let query = match fill_in_answer(query, answers) {
+        Ok(query) => query,
+        Err(reply) => return reply,
+    };
+    let mut rows = match parse_words_list(&query) {
+        Ok(rows) => rows,
+        Err(e) => {
+            dbg!(e);
+            return "Weird, couldn't parse your message, I need 5 letters per row.".to_string();
+        }
+    };
+    let scores = match calculate_guess_scores(&rows) {
+        Ok(scores) => scores,
+        Err(e) => {
+            dbg!(e);
+            return "Weird, something went wrong running, not sure what".to_string();
+        }
+    };
+    let res = match calculate_word_possibilities(&mut rows, words_to_show) {
+        Ok(res) => res,
+        Err(e) => {
+            dbg!(e);
+            return "Weird, something went wrong running, not sure what".to_string();
+        }
+    };
+    let mut result = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let score_str = match &scores[i] {
+            Some(score) if score.best.word != row.iter().collect::<String>() => format!(
+                " skill {} luck {}, best was {} (~{:.1} left)",
+                score.skill,
+                score.luck,
+                word_tag(&score.best.word, spoilers),
+                score.best.expected_remaining
+            ),
+            Some(score) => {
+                format!(" skill {} luck {}, best move!", score.skill, score.luck)
+            }
+            None => String::new(),
+        };
+        let row_str = format!(
+            "{} - {}({}): ({}){}\n",
+            word_tag(&row.iter().collect::<String>(), spoilers),
+            res[i].2,
+            res[i].1,
+            res[i]
+                .0
+                .iter()
+                .map(|word| word_tag(word, spoilers))
+                .collect::<Vec<_>>()
+                .join(", "),
+            score_str,
+        );
+        result.push_str(&row_str);
+    }
+    if let Some((skill, luck)) = summarize_guess_scores(&scores) {
+        result.push_str(&format!("Skill {}/99, Luck {}/99", skill, luck));
+    }
+    result
+}
+
+// The solver takes long enough that it can't run on the async workers, which
+// also keep the gateway connection alive, so it gets a thread of its own.
+pub async fn run_wordle_stats(
+    query: &str,
+    spoilers: bool,
+    answers: &Arc<AnswerList>,
+    words_to_show: usize,
+) -> String {
+    let (query, answers) = (query.to_string(), answers.clone());
+    tokio::task::spawn_blocking(move || wordle_stats(&query, spoilers, &answers, words_to_show))
+        .await
+        .unwrap_or_else(|e| {
+            dbg!(e);
+            "Weird, something went wrong running, not sure what".to_string()
+        })
+}
+
+// The answer to a grid can be given straight or as a wordle number. Without
+// either it's the one from the pasted share, or failing that today's.
+fn path_answer(
+    answers: &AnswerList,
+    given: Option<&str>,
+    grid: &str,
+    today: NaiveDate,
+) -> Result<String, String> {
+    if let Some(given) = given {
+        return match parse_wordle_number(given) {
+            Some(day) => scheduled_answer(answers, day),
+            None => Ok(given.to_string()),
+        };
+    }
+    let day = match game_or_default(DEFAULT_GAME).parse(grid) {
+        Some(share) => share.day,
+        None => day_for_date(today).ok_or_else(|| "Weird, what day is it?".to_string())?,
+    };
+    scheduled_answer(answers, day)
+}
+
+pub fn wordle_path(
+    answers: &AnswerList,
+    answer: Option<&str>,
+    grid: &str,
+    today: NaiveDate,
+) -> String {
+    let answer = match path_answer(answers, answer, grid, today) {
+        Ok(answer) => answer,
+        Err(reply) => return reply,
+    };
+    let (answer, rows) = match (wordle_word_to_char_array(&answer), parse_grid(grid)) {
+        (Ok(answer), Ok(rows)) => (answer, rows),
+        (Err(e), _) | (_, Err(e)) => {
+            dbg!(e);
+            return "Weird, couldn't parse your message, I need a 5 letter answer and a grid."
+                .to_string();
+        }
+    };
+    let counts = calculate_pattern_possibilities(answer, &rows);
+    // Draw the rows back the way they see them
+    let theme = detect_theme(grid);
+    let mut result = format!("Your path to ||`{}`||\n", answer.iter().collect::<String>());
+    for (row, (valid, total)) in rows.iter().zip(counts.iter()) {
+        result.push_str(&format!(
+            "{} - {}({}) words could do that\n",
+            row_to_emoji(row, theme),
+            total,
+            valid
+        ));
+    }
+    result
+}
+
+fn game_results<'a>(results: &'a [GameResult], game: &str) -> Vec<&'a GameResult> {
+    results
+        .iter()
+        .filter(|r| r.game.eq_ignore_ascii_case(game))
+        .collect()
+}
+
+pub fn stats(results: &[GameResult], user_id: u64, game: Option<&str>) -> String {
+    let game = game.unwrap_or(DEFAULT_GAME);
+    let game_results = game_results(results, game);
+    let user_results: Vec<_> = game_results
+        .iter()
+        .filter(|r| r.user_id == user_id)
+        .copied()
+        .collect();
+    match user_results.first() {
+        Some(first) => {
+            let latest_day = game_results.iter().map(|r| r.day).max().unwrap_or(0);
+            let stats = calculate_stats(user_results.iter().copied(), latest_day);
+            let mut msg = format!(
+                "{} stats for <@{}>\n{}",
+                first.game,
+                user_id,
+                format_stats(&stats)
+            );
+            let hard_mode: Vec<_> = user_results
+                .iter()
+                .filter(|r| r.hard_mode)
+                .copied()
+                .collect();
+            if !hard_mode.is_empty() {
+                let hard_stats = calculate_stats(hard_mode, latest_day);
+                msg.push_str(&format!("\n{}", format_hard_mode(&hard_stats)));
+            }
+            msg
+        }
+        None => format!("No {} results for <@{}> yet!", game, user_id),
+    }
+}
+
+pub fn leaderboard(results: &[GameResult], game: Option<&str>, window: Window) -> Reply {
+    let game = game.unwrap_or(DEFAULT_GAME);
+    let game_results = game_results(results, game);
+    let game_name = match game_results.first() {
+        Some(first) => first.game.clone(),
+        None => return Reply::Text(format!("No {} results yet!", game)),
+    };
+    let entries = calculate_leaderboard(game_results, window);
+    Reply::Embed {
+        title: format!("{} leaderboard, {}", game_name, window.name()),
+        description: format_leaderboard(&entries),
+        footer: "Ranked by average guesses (X counts as 7), evened out towards the server average"
+            .to_string(),
+    }
+}
+
+// Game names matching what's been typed so far, for autocomplete. Games the
+// guild has actually played come first.
+pub fn game_names(results: &[GameResult], typed: &str) -> Vec<String> {
+    let played: BTreeSet<_> = results.iter().map(|r| r.game.as_str()).collect();
+    let typed = typed.to_lowercase();
+    let mut names: Vec<String> = vec![];
+    for name in played.iter().copied().chain(GAMES.iter().map(|g| g.name())) {
+        if name.to_lowercase().starts_with(&typed)
+            && !names.iter().any(|n| n.eq_ignore_ascii_case(name))
+        {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{fill_in_answer, game_names, leaderboard, stats, wordle_path, wordle_stats, Reply};
+    use crate::answers::AnswerList;
+    use crate::detector::DEFAULT_WORDS_TO_SHOW;
+    use crate::stats::Window;
+    use crate::store::{test_result as result, Score};
+
+    #[test]
+    fn test_wordle_stats() {
+        let answers = AnswerList::bundled();
+        let reply = wordle_stats("soare\nabide", false, &answers, DEFAULT_WORDS_TO_SHOW);
+        assert!(reply.starts_with("`soare` - "));
+        assert!(reply.contains("best move!"));
+        assert!(reply.contains("Skill 99/99"));
+        assert_eq!(
+            wordle_stats("soar\nabide", false, &answers, DEFAULT_WORDS_TO_SHOW),
+            "Weird, couldn't parse your message, I need 5 letters per row."
+        );
+    }
+
+    #[test]
+    fn test_wordle_stats_spoilers() {
+        let reply = wordle_stats(
+            "soare\nabide",
+            true,
+            &AnswerList::bundled(),
+            DEFAULT_WORDS_TO_SHOW,
+        );
+        // The example words are picked at random
+        assert!(reply.starts_with("||`soare`|| - 156(40): (||`"));
+        assert!(!reply.contains("(`"));
+        assert!(!reply.contains(", `"));
+    }
+
+    #[test]
+    fn test_fill_in_answer() {
+        let answers = AnswerList::bundled();
+        assert_eq!(
+            fill_in_answer("soare\nabide", &answers).unwrap(),
+            "soare\nabide"
+        );
+        assert_eq!(
+            fill_in_answer("#229\nsoare", &answers).unwrap(),
+            "soare\nshard"
+        );
+        assert_eq!(
+            fill_in_answer("229\nsoare\nSHARD", &answers).unwrap(),
+            "soare\nSHARD"
+        );
+        assert!(fill_in_answer("100000\nsoare", &answers).is_err());
+        // The word list doesn't know what the editor picked
+        assert!(fill_in_answer("1950\nsoare", &answers).is_err());
+    }
+
+    #[test]
+    fn test_wordle_path() {
+        let answers = AnswerList::bundled();
+        let today = NaiveDate::from_ymd_opt(2022, 2, 4).unwrap();
+        let path = |answer, grid| wordle_path(&answers, answer, grid, today);
+        let solved = "Your path to ||`abide`||\n🟩🟩🟩🟩🟩 - 1(1) words could do that\n";
+        assert_eq!(path(Some("abide"), "Wordle 229 1/6\n🟩🟩🟩🟩🟩"), solved);
+        assert!(path(Some("abide"), "no grid here").starts_with("Weird"));
+
+        // The answer from the wordle number, the share or the date
+        let shard = "Your path to ||`shard`||\n🟩🟩🟩🟩🟩 - 1(1) words could do that\n";
+        assert_eq!(path(Some("229"), "🟩🟩🟩🟩🟩"), shard);
+        assert_eq!(path(None, "Wordle 229 1/6\n\n🟩🟩🟩🟩🟩"), shard);
+        assert!(path(None, "🟩🟩🟩🟩🟩").starts_with("Your path to ||`pleat`||"));
+    }
+
+    #[test]
+    fn test_stats() {
+        let results = vec![
+            result(1, "Wordle", 1, Score::Solved(3)),
+            result(1, "Heardle", 1, Score::Unscored),
+        ];
+        assert!(stats(&results, 1, None).starts_with("Wordle stats for <@1>\nPlayed: 1"));
+        assert!(stats(&results, 1, Some("heardle")).starts_with("Heardle stats for <@1>"));
+        assert!(!stats(&results, 1, None).contains("Hard mode"));
+        let mut hard = result(1, "Wordle", 2, Score::Solved(4));
+        hard.hard_mode = true;
+        let results = vec![results[0].clone(), hard];
+        assert!(stats(&results, 1, None)
+            .ends_with("\nHard mode: Played: 1 | Win %: 100 | Mean guesses: 4.00"));
+        assert_eq!(stats(&results, 2, None), "No Wordle results for <@2> yet!");
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let results = vec![result(1, "Wordle", 1, Score::Solved(3))];
+        match leaderboard(&results, Some("wordle"), Window::Week) {
+            Reply::Embed { title, .. } => assert_eq!(title, "Wordle leaderboard, this week"),
+            reply => panic!("expected an embed, got {:?}", reply),
+        }
+        assert_eq!(
+            leaderboard(&results, Some("Heardle"), Window::All),
+            Reply::Text("No Heardle results yet!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_game_names() {
+        let results = vec![result(1, "Nerdle", 1, Score::Solved(3))];
+        assert_eq!(game_names(&results, "")[..2], ["Nerdle", "Wordle"]);
+        assert_eq!(game_names(&results, "q"), vec!["Quordle"]);
+        assert_eq!(game_names(&results, "WOR"), vec!["Wordle", "Worldle"]);
+        assert!(game_names(&results, "zzz").is_empty());
+    }
+}
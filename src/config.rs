@@ -0,0 +1,365 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::answers::AnswerList;
+use crate::detector::DEFAULT_WORDS_TO_SHOW;
+use crate::gate::GateMode;
+use crate::guard::GuardAction;
+use crate::recap::{parse_schedules, RecapSchedule};
+use crate::settings::GuildSettings;
+use crate::threads::parse_archive_duration;
+
+// Where the config file is looked for if WORDLE_CONFIG doesn't say
+const DEFAULT_CONFIG_FILE: &str = "wordlebot.toml";
+
+pub struct Config {
+    pub token: String,
+    // Needed to register slash commands, they're skipped without it
+    pub application_id: Option<u64>,
+    pub store: String,
+    pub recaps: Vec<RecapSchedule>,
+    // Shared with the threads the solver runs on
+    pub answers: Arc<AnswerList>,
+    // How many of the possible words `!wordlestats` shows for each row
+    pub words_to_show: usize,
+    // Each guild's settings from the file and the environment. Anything
+    // changed with `!wordlebot config` goes on top of these.
+    pub guilds: HashMap<u64, GuildSettings>,
+}
+
+// What can go in the config file, everything's optional so the environment
+// can fill it in
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    token: Option<String>,
+    application_id: Option<u64>,
+    store: Option<String>,
+    answers: Option<String>,
+    words_to_show: Option<usize>,
+    // `<guild id>:<channel id>@<HH:MM>`, like WORDLE_RECAPS
+    recaps: Vec<String>,
+    // By guild id, toml keys have to be strings
+    guilds: HashMap<String, GuildSettings>,
+}
+
+// Where `!wordlestats` replies go, since they're full of this puzzle's words.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpoilerPolicy {
+    // A DM, or only visible to you for the slash command
+    #[serde(alias = "dm")]
+    Private,
+    // Your solvers thread for your last wordle
+    Thread,
+    // Right there in the channel, with every word in spoiler tags
+    Spoiler,
+}
+
+impl SpoilerPolicy {
+    pub fn parse(policy: &str) -> Option<SpoilerPolicy> {
+        match policy.trim().to_lowercase().as_str() {
+            "private" | "dm" => Some(SpoilerPolicy::Private),
+            "thread" => Some(SpoilerPolicy::Thread),
+            "spoiler" => Some(SpoilerPolicy::Spoiler),
+            _ => None,
+        }
+    }
+}
+
+impl Config {
+    // The config file (WORDLE_CONFIG, or wordlebot.toml if there is one) with
+    // the environment on top
+    pub fn from_env() -> anyhow::Result<Config> {
+        let file = match env::var("WORDLE_CONFIG") {
+            Ok(path) => Some(path),
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(DEFAULT_CONFIG_FILE.to_string())
+            }
+            Err(_) => None,
+        };
+        let file = match file {
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("couldn't read config file {}: {}", path, e))?,
+            None => String::new(),
+        };
+        Config::load(&file, |name| env::var(name).ok())
+    }
+
+    fn load(file: &str, var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Config> {
+        let file: ConfigFile =
+            toml::from_str(file).map_err(|e| anyhow::anyhow!("bad config file: {}", e))?;
+        let token = var("WORDLE_TOKEN")
+            .or(file.token)
+            .ok_or_else(|| anyhow::anyhow!("Expected a token in the environment"))?;
+        let application_id = match var("WORDLE_APPLICATION_ID") {
+            Some(id) => Some(
+                id.parse()
+                    .map_err(|_| anyhow::anyhow!("bad WORDLE_APPLICATION_ID {}", id))?,
+            ),
+            None => file.application_id,
+        };
+        let store = var("WORDLE_STORE")
+            .or(file.store)
+            .unwrap_or_else(|| "file:wordlebot.jsonl".to_string());
+        let recaps =
+            parse_schedules(&var("WORDLE_RECAPS").unwrap_or_else(|| file.recaps.join(",")))?;
+        let answers = match var("WORDLE_ANSWERS").or(file.answers) {
+            Some(path) => Arc::new(AnswerList::load(path)?),
+            None => Arc::new(AnswerList::bundled()),
+        };
+        let words_to_show = match var("WORDLE_WORDS_TO_SHOW") {
+            Some(count) => count
+                .parse()
+                .map_err(|_| anyhow::anyhow!("bad WORDLE_WORDS_TO_SHOW {}", count))?,
+            None => file.words_to_show.unwrap_or(DEFAULT_WORDS_TO_SHOW),
+        };
+
+        let mut guilds = HashMap::new();
+        for (guild_id, settings) in file.guilds {
+            let guild_id: u64 = guild_id.parse().map_err(|_| {
+                anyhow::anyhow!("expected a guild id in the config file, got {}", guild_id)
+            })?;
+            settings
+                .validate()
+                .map_err(|e| anyhow::anyhow!("bad settings for guild {}: {}", guild_id, e))?;
+            guilds.insert(guild_id, settings);
+        }
+        for guild_id in parse_ids(&var("WORDLE_SLASH_ONLY_GUILDS").unwrap_or_default())? {
+            guilds.entry(guild_id).or_default().slash_only = Some(true);
+        }
+        for (guild_id, policy) in parse_guild_settings(
+            &var("WORDLE_SPOILER_POLICIES").unwrap_or_default(),
+            "private|thread|spoiler",
+            SpoilerPolicy::parse,
+        )? {
+            guilds.entry(guild_id).or_default().spoiler_policy = Some(policy);
+        }
+        for (guild_id, action) in parse_guild_settings(
+            &var("WORDLE_SPOILER_GUARDS").unwrap_or_default(),
+            "off|delete|repost|warn",
+            GuardAction::parse,
+        )? {
+            guilds.entry(guild_id).or_default().spoiler_guard = Some(action);
+        }
+        for (guild_id, gate) in parse_guild_settings(
+            &var("WORDLE_THREAD_GATES").unwrap_or_default(),
+            "open|members|role",
+            GateMode::parse,
+        )? {
+            guilds.entry(guild_id).or_default().thread_gate = Some(gate);
+        }
+        for (guild_id, duration) in parse_guild_settings(
+            &var("WORDLE_THREAD_ARCHIVE").unwrap_or_default(),
+            "1h|1d|3d|1w",
+            |duration| parse_archive_duration(duration).map(|_| duration.trim().to_lowercase()),
+        )? {
+            guilds.entry(guild_id).or_default().thread_archive = Some(duration);
+        }
+        Ok(Config {
+            token,
+            application_id,
+            store,
+            recaps,
+            answers,
+            words_to_show,
+            guilds,
+        })
+    }
+}
+
+// A comma separated list of discord ids
+fn parse_ids(ids: &str) -> anyhow::Result<HashSet<u64>> {
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .map_err(|_| anyhow::anyhow!("expected a discord id, got {}", id))
+        })
+        .collect()
+}
+
+// A comma separated list of `<guild id>:<setting>`
+fn parse_guild_settings<T>(
+    settings: &str,
+    expected: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> anyhow::Result<HashMap<u64, T>> {
+    settings
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let bad_entry = || anyhow::anyhow!("expected <guild id>:<{}>, got {}", expected, entry);
+            let (guild_id, setting) = entry.split_once(':').ok_or_else(bad_entry)?;
+            Ok((
+                guild_id.trim().parse().map_err(|_| bad_entry())?,
+                parse(setting).ok_or_else(bad_entry)?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, SpoilerPolicy};
+    use crate::gate::GateMode;
+    use crate::guard::GuardAction;
+    use crate::settings::GuildSettings;
+    use crate::threads::{ThreadMode, ThreadStrategy};
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> anyhow::Result<Config> {
+        config_with_file("", vars)
+    }
+
+    fn config_with_file(file: &str, vars: &[(&str, &str)]) -> anyhow::Result<Config> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        Config::load(file, |name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    fn guild(config: &Config, guild_id: u64) -> GuildSettings {
+        config.guilds.get(&guild_id).cloned().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config(&[("WORDLE_TOKEN", "abc")]).unwrap();
+        assert_eq!(config.token, "abc");
+        assert_eq!(config.application_id, None);
+        assert_eq!(config.store, "file:wordlebot.jsonl");
+        assert!(config.recaps.is_empty());
+        assert_eq!(config.words_to_show, 4);
+        assert!(config.guilds.is_empty());
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let config = config(&[
+            ("WORDLE_TOKEN", "abc"),
+            ("WORDLE_APPLICATION_ID", "123"),
+            ("WORDLE_STORE", "sqlite:wordlebot.db"),
+            ("WORDLE_RECAPS", "1:2@09:30"),
+            ("WORDLE_WORDS_TO_SHOW", "6"),
+            ("WORDLE_SLASH_ONLY_GUILDS", "1, 2"),
+            ("WORDLE_SPOILER_POLICIES", "1:thread, 2:Spoiler, 3:dm"),
+            ("WORDLE_SPOILER_GUARDS", "1:repost"),
+            ("WORDLE_THREAD_GATES", "2:role"),
+            ("WORDLE_THREAD_ARCHIVE", "2:3d"),
+        ])
+        .unwrap();
+        assert_eq!(config.application_id, Some(123));
+        assert_eq!(config.store, "sqlite:wordlebot.db");
+        assert_eq!(config.recaps.len(), 1);
+        assert_eq!(config.words_to_show, 6);
+        assert!(guild(&config, 1).slash_only());
+        assert!(guild(&config, 2).slash_only());
+        assert!(!guild(&config, 3).slash_only());
+        assert_eq!(guild(&config, 1).spoiler_policy(), SpoilerPolicy::Thread);
+        assert_eq!(guild(&config, 2).spoiler_policy(), SpoilerPolicy::Spoiler);
+        assert_eq!(guild(&config, 3).spoiler_policy(), SpoilerPolicy::Private);
+        assert_eq!(guild(&config, 4).spoiler_policy(), SpoilerPolicy::Private);
+        assert_eq!(guild(&config, 1).spoiler_guard(), GuardAction::Repost);
+        assert_eq!(guild(&config, 2).spoiler_guard(), GuardAction::Off);
+        assert_eq!(guild(&config, 2).thread_gate(), GateMode::Role);
+        assert_eq!(guild(&config, 2).archive_duration(), Some(4320));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let file = r#"
+            token = "abc"
+            store = "sqlite:wordlebot.db"
+            words_to_show = 2
+            recaps = ["1:2@09:30", "3:4@23:00"]
+
+            [guilds.1]
+            prefix = "?"
+            games = ["Wordle", "Heardle"]
+            thread_mode = "public"
+            solvers_channel = "spoilers"
+            spoiler_policy = "thread"
+
+            [guilds.1.channels]
+            10 = "forum #solvers"
+
+            [guilds.2]
+            thread_gate = "members"
+        "#;
+        let config = config_with_file(file, &[]).unwrap();
+        assert_eq!(config.token, "abc");
+        assert_eq!(config.store, "sqlite:wordlebot.db");
+        assert_eq!(config.words_to_show, 2);
+        assert_eq!(config.recaps.len(), 2);
+        let settings = guild(&config, 1);
+        assert_eq!(settings.prefix(), "?");
+        assert!(!settings.game_enabled("Quordle"));
+        assert_eq!(settings.thread_mode(), ThreadMode::Public);
+        assert_eq!(settings.solvers_channel("general"), "spoilers");
+        assert_eq!(settings.spoiler_policy(), SpoilerPolicy::Thread);
+        assert_eq!(
+            settings.thread_strategy(10, "general"),
+            Some(ThreadStrategy::Forum("solvers".to_string()))
+        );
+        assert_eq!(guild(&config, 2).thread_gate(), GateMode::Members);
+
+        // The environment wins
+        let config = config_with_file(
+            file,
+            &[
+                ("WORDLE_TOKEN", "xyz"),
+                ("WORDLE_SPOILER_POLICIES", "1:spoiler"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.token, "xyz");
+        assert_eq!(guild(&config, 1).spoiler_policy(), SpoilerPolicy::Spoiler);
+        assert_eq!(guild(&config, 1).prefix(), "?");
+    }
+
+    #[test]
+    fn test_bad_file() {
+        let token = [("WORDLE_TOKEN", "abc")];
+        assert!(config_with_file("token = ", &token).is_err());
+        assert!(config_with_file("colour = \"blue\"", &token).is_err());
+        assert!(config_with_file("[guilds.general]\nprefix = \"?\"", &token).is_err());
+        assert!(config_with_file("[guilds.1]\nthread_mode = \"secret\"", &token).is_err());
+        assert!(config_with_file("[guilds.1]\ngames = [\"chess\"]", &token).is_err());
+        assert!(config_with_file("[guilds.1]\nthread_archive = \"2d\"", &token).is_err());
+        assert!(config_with_file("[guilds.1.channels]\n10 = \"forum\"", &token).is_err());
+        assert!(config_with_file("[guilds.1.channels]\ngeneral = \"private\"", &token).is_err());
+    }
+
+    #[test]
+    fn test_bad_vars() {
+        assert!(config(&[]).is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_APPLICATION_ID", "me")]).is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_WORDS_TO_SHOW", "lots")]).is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_SLASH_ONLY_GUILDS", "a,b")]).is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_RECAPS", "1")]).is_err());
+        assert!(config(&[
+            ("WORDLE_TOKEN", "abc"),
+            ("WORDLE_SPOILER_POLICIES", "1:loud")
+        ])
+        .is_err());
+        assert!(config(&[
+            ("WORDLE_TOKEN", "abc"),
+            ("WORDLE_SPOILER_POLICIES", "thread")
+        ])
+        .is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_SPOILER_GUARDS", "1:ban")]).is_err());
+        assert!(config(&[
+            ("WORDLE_TOKEN", "abc"),
+            ("WORDLE_THREAD_GATES", "1:everyone")
+        ])
+        .is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_THREAD_ARCHIVE", "1:2d")]).is_err());
+        assert!(config(&[("WORDLE_TOKEN", "abc"), ("WORDLE_ANSWERS", "/no/such/file")]).is_err());
+    }
+}
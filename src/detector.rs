@@ -4,42 +4,87 @@ use crate::words::{EXTRA_WORDS, VALID_WORDS};
 use lazy_static::lazy_static;
 use rand::seq::IteratorRandom;
 
-
-const WORDS_TO_SHOW: usize = 4;
+// How many of the possible words `!wordlestats` shows per row, unless the
+// config says otherwise
+pub const DEFAULT_WORDS_TO_SHOW: usize = 4;
 
 lazy_static! {
     static ref EXTRA_WORDS_CHARS: Vec<PreparsedWord> = must_convert_list_to_char_list(EXTRA_WORDS);
     static ref VALID_WORDS_CHARS: Vec<PreparsedWord> = must_convert_list_to_char_list(VALID_WORDS);
+    // Anything wordle accepts as a guess
+    static ref GUESS_WORDS_CHARS: Vec<&'static PreparsedWord> =
+        VALID_WORDS_CHARS.iter().chain(EXTRA_WORDS_CHARS.iter()).collect();
+    // Every game starts out the same, so only work out the best opener once
+    static ref OPENING_GUESS: RankedGuess =
+        rank_guesses_for(&VALID_WORDS_CHARS.iter().collect::<Vec<_>>()).remove(0);
 }
 
 pub struct PreparsedWord {
     word: [char; 5],
-    chars: HashSet<char>,
+    counts: [u8; 26],
 }
 
 impl PreparsedWord {
     fn new(word: [char; 5]) -> PreparsedWord {
-        let mut wordletters: HashSet<char> = HashSet::new();
-        for i in 0..5 {
-            wordletters.insert(word[i]);
-        }
-        PreparsedWord {
-            word,
-            chars: wordletters,
+        let mut counts = [0; 26];
+        for letter in word.iter() {
+            counts[letter_index(*letter)] += 1;
         }
+        PreparsedWord { word, counts }
     }
 }
 
+fn letter_index(letter: char) -> usize {
+    (letter as u8 - b'a') as usize
+}
+
 pub fn must_convert_list_to_char_list(words: &'static [&'static str]) -> Vec<PreparsedWord> {
     words
-        .into_iter()
+        .iter()
         .map(|word| {
-            let ch = wordle_word_to_char_array(*word).unwrap();
+            let ch = wordle_word_to_char_array(word).unwrap();
             PreparsedWord::new(ch)
         })
         .collect()
 }
 
+// The colour of a single tile after a guess.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    // Grey, the letter isn't in the word (or not that many times)
+    Absent,
+    // Yellow, the letter is in the word but somewhere else
+    Present,
+    // Green, the letter is in the right spot
+    Correct,
+}
+
+// Score a guess against the answer the same way wordle does. Greens are handed
+// out first, then yellows left to right while there are unused copies of the
+// letter left in the answer, so "speed" against "abide" only gets one yellow e.
+pub fn score_guess(guess: [char; 5], answer: [char; 5]) -> [Tile; 5] {
+    let mut tiles = [Tile::Absent; 5];
+    let mut unused = [0u8; 26];
+    for i in 0..5 {
+        if guess[i] == answer[i] {
+            tiles[i] = Tile::Correct;
+        } else {
+            unused[letter_index(answer[i])] += 1;
+        }
+    }
+    for i in 0..5 {
+        if tiles[i] == Tile::Correct {
+            continue;
+        }
+        let idx = letter_index(guess[i]);
+        if unused[idx] > 0 {
+            unused[idx] -= 1;
+            tiles[i] = Tile::Present;
+        }
+    }
+    tiles
+}
+
 #[derive(Clone)]
 struct Position {
     found_letter: Option<char>,
@@ -55,17 +100,18 @@ impl Position {
     }
 }
 
-struct Validator {
-    wordleword: PreparsedWord,
+pub struct Validator {
+    wordleword: [char; 5],
     letter_positions: [Position; 5],
-    missed_letters: HashSet<char>,
-    wrong_pos_letters: HashSet<char>,
+    // How many times each letter is known to appear, at least and at most.
+    min_counts: [u8; 26],
+    max_counts: [u8; 26],
 }
 
 impl Validator {
-    fn new(wordleword: [char; 5]) -> Validator {
+    pub fn new(wordleword: [char; 5]) -> Validator {
         Validator {
-            wordleword: PreparsedWord::new(wordleword),
+            wordleword,
             letter_positions: [
                 Position::new(),
                 Position::new(),
@@ -73,55 +119,87 @@ impl Validator {
                 Position::new(),
                 Position::new(),
             ],
-            missed_letters: HashSet::new(),
-            wrong_pos_letters: HashSet::new(),
+            min_counts: [0; 26],
+            max_counts: [5; 26],
         }
     }
 
-    fn injest_word(&mut self, word: [char; 5]) {
+    pub fn injest_word(&mut self, word: [char; 5]) {
+        let tiles = score_guess(word, self.wordleword);
+        self.injest_feedback(word, tiles);
+    }
+
+    pub fn injest_feedback(&mut self, word: [char; 5], tiles: [Tile; 5]) {
+        let mut seen = [0u8; 26];
+        let mut capped = [false; 26];
         for i in 0..5 {
             let letter = word[i];
-            if letter == self.wordleword.word[i] {
-                // MATCH!
-                self.letter_positions[i].found_letter = Some(letter)
-            } else if self.wordleword.chars.contains(&letter) {
-                // MISS BUT IN WORD!
-                self.letter_positions[i].invalid_letters.insert(letter);
-                self.wrong_pos_letters.insert(letter);
-            } else {
-                // COMPLETE MISS!
-                self.missed_letters.insert(letter);
+            let idx = letter_index(letter);
+            match tiles[i] {
+                Tile::Correct => {
+                    // MATCH!
+                    self.letter_positions[i].found_letter = Some(letter);
+                    seen[idx] += 1;
+                }
+                Tile::Present => {
+                    // MISS BUT IN WORD!
+                    self.letter_positions[i].invalid_letters.insert(letter);
+                    seen[idx] += 1;
+                }
+                Tile::Absent => {
+                    // MISS! Either the letter isn't in the word at all, or we've
+                    // already used up every copy of it elsewhere in this guess.
+                    self.letter_positions[i].invalid_letters.insert(letter);
+                    capped[idx] = true;
+                }
+            }
+        }
+        for idx in 0..26 {
+            if seen[idx] > self.min_counts[idx] {
+                self.min_counts[idx] = seen[idx];
+            }
+            if capped[idx] && seen[idx] < self.max_counts[idx] {
+                self.max_counts[idx] = seen[idx];
             }
         }
     }
 
-    fn valid_for_word(&self, word: &PreparsedWord) -> bool {
-        // Filter out words with letters we know are _not_ in the word
-        for ch in self.missed_letters.iter() {
-            if word.chars.contains(ch) {
-                return false;
-            }
+    // The possible answers that fit everything guessed so far. Falls back on the
+    // extra words when the answer isn't one of the regular wordle answers.
+    pub fn candidates(&self) -> Vec<&'static PreparsedWord> {
+        let candidates: Vec<_> = VALID_WORDS_CHARS
+            .iter()
+            .filter(|word| self.valid_for_word(word))
+            .collect();
+        if !candidates.is_empty() {
+            return candidates;
         }
+        EXTRA_WORDS_CHARS
+            .iter()
+            .filter(|word| self.valid_for_word(word))
+            .collect()
+    }
 
-        // Filter out words that don't have the letters we know are in the word
-        for ch in self.wrong_pos_letters.iter() {
-            if !word.chars.contains(ch) {
+    fn valid_for_word(&self, word: &PreparsedWord) -> bool {
+        // Filter out words that don't use each letter the right number of times
+        for idx in 0..26 {
+            let count = word.counts[idx];
+            if count < self.min_counts[idx] || count > self.max_counts[idx] {
                 return false;
             }
         }
 
         // Filter out individual letters positions
-        for i in 0..5 {
-            let letter = word.word[i];
+        for (letter, position) in word.word.iter().zip(self.letter_positions.iter()) {
             // Check if the letter matches our _exact_ need
-            if let Some(found_letter) = self.letter_positions[i].found_letter {
-                if found_letter != letter {
+            if let Some(found_letter) = position.found_letter {
+                if found_letter != *letter {
                     return false;
                 }
             }
 
             // Check if the letter is invalid based on previous guesses
-            if self.letter_positions[i].invalid_letters.contains(&letter) {
+            if position.invalid_letters.contains(letter) {
                 return false;
             }
         }
@@ -129,16 +207,93 @@ impl Validator {
     }
 }
 
+// How good a guess is against the answers that are still possible.
+#[derive(Clone, Debug)]
+pub struct RankedGuess {
+    pub word: String,
+    // Expected information (in bits) from the colours the guess gets back
+    pub entropy: f64,
+    // How many possible answers we expect to have left after the guess
+    pub expected_remaining: f64,
+    // Whether the guess could be the answer itself
+    pub is_candidate: bool,
+}
+
+// Squash the colours of a row into a single number in 0..243
+fn tiles_code(tiles: &[Tile; 5]) -> usize {
+    tiles.iter().fold(0, |code, tile| code * 3 + *tile as usize)
+}
+
+fn pattern_code(guess: [char; 5], answer: [char; 5]) -> usize {
+    tiles_code(&score_guess(guess, answer))
+}
+
+const SOLVED_CODE: usize = 242;
+
+// How many of the candidates would come back with each pattern for this guess
+fn pattern_buckets(guess: &PreparsedWord, candidates: &[&PreparsedWord]) -> [u32; 243] {
+    let mut buckets = [0u32; 243];
+    for candidate in candidates.iter() {
+        buckets[pattern_code(guess.word, candidate.word)] += 1;
+    }
+    buckets
+}
+
+fn rank_guess(guess: &PreparsedWord, candidates: &[&PreparsedWord]) -> RankedGuess {
+    let buckets = pattern_buckets(guess, candidates);
+    let is_candidate = buckets[SOLVED_CODE] > 0;
+    let total = candidates.len() as f64;
+    let mut entropy = 0.0;
+    let mut expected_remaining = 0.0;
+    for count in buckets.iter().filter(|count| **count > 0) {
+        let p = *count as f64 / total;
+        entropy -= p * p.log2();
+        expected_remaining += p * *count as f64;
+    }
+    RankedGuess {
+        word: guess.word.iter().collect(),
+        entropy,
+        expected_remaining,
+        is_candidate,
+    }
+}
+
+fn rank_guesses_for(candidates: &[&PreparsedWord]) -> Vec<RankedGuess> {
+    let mut ranked: Vec<_> = GUESS_WORDS_CHARS
+        .iter()
+        .map(|guess| rank_guess(guess, candidates))
+        .collect();
+    // Most information first, and when it's a tie go for a guess that could win
+    ranked.sort_by(|a, b| {
+        b.entropy
+            .partial_cmp(&a.entropy)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.is_candidate.cmp(&a.is_candidate))
+            .then(a.word.cmp(&b.word))
+    });
+    ranked
+}
+
+// Out of every allowed guess, the one that's expected to tell us the most.
+// None if no word fits what's been guessed so far.
+pub fn best_guess(validator: &Validator) -> Option<RankedGuess> {
+    let candidates = validator.candidates();
+    match candidates.len() {
+        0 => None,
+        n if n == VALID_WORDS_CHARS.len() => Some(OPENING_GUESS.clone()),
+        _ => rank_guesses_for(&candidates).into_iter().next(),
+    }
+}
+
 pub fn parse_words_list(words: &str) -> anyhow::Result<Vec<[char; 5]>> {
-    words.split('\n').into_iter()
-        .map(wordle_word_to_char_array)
-        .collect()
+    words.split('\n').map(wordle_word_to_char_array).collect()
 }
 
 // Taking in a list of wordle words, calculate how many "valid" guesses were possible
 // at each step.
 pub fn calculate_word_possibilities(
     words: &mut Vec<[char; 5]>,
+    words_to_show: usize,
 ) -> anyhow::Result<Vec<(Vec<String>, u32, u32)>> {
     let mut rng = rand::thread_rng();
     let wordleword = words
@@ -150,26 +305,26 @@ pub fn calculate_word_possibilities(
     let mut num_word_chances: Vec<(Vec<String>, u32, u32)> = vec![];
     for word in words {
         validator.injest_word(*word);
-        let valid_words: Vec<_> = (*VALID_WORDS_CHARS)
+        let valid_words: Vec<_> = VALID_WORDS_CHARS
             .iter()
-            .filter(|word| validator.valid_for_word(*word))
+            .filter(|word| validator.valid_for_word(word))
             .collect();
         let num_valid_words = valid_words.len() as u32;
         let mut top_five: Vec<_> = valid_words
             .iter()
-            .choose_multiple(&mut rng, WORDS_TO_SHOW)
+            .choose_multiple(&mut rng, words_to_show)
             .into_iter()
             .map(|p| p.word.iter().collect::<String>())
             .collect();
-        let extra_words: Vec<_> = (*EXTRA_WORDS_CHARS)
+        let extra_words: Vec<_> = EXTRA_WORDS_CHARS
             .iter()
-            .filter(|word| validator.valid_for_word(*word))
+            .filter(|word| validator.valid_for_word(word))
             .collect();
         let num_extra_words = extra_words.len() as u32;
-        if top_five.len() < WORDS_TO_SHOW {
+        if top_five.len() < words_to_show {
             let mut extra_rows = extra_words
                 .iter()
-                .choose_multiple(&mut rng, WORDS_TO_SHOW - top_five.len())
+                .choose_multiple(&mut rng, words_to_show - top_five.len())
                 .into_iter()
                 .map(|p| p.word.iter().collect::<String>())
                 .collect();
@@ -181,6 +336,118 @@ pub fn calculate_word_possibilities(
     Ok(num_word_chances)
 }
 
+// How well a single row was played, both out of 99 like the NYT WordleBot.
+#[derive(Clone, Debug)]
+pub struct GuessScore {
+    // How much the guess was expected to tell us next to the best guess
+    pub skill: u32,
+    // How the words left over compare to every other way the guess could have
+    // come back, 99 being the best outcome possible
+    pub luck: u32,
+    pub best: RankedGuess,
+}
+
+fn skill_score(guess: &RankedGuess, best: &RankedGuess) -> u32 {
+    if best.entropy <= 0.0 {
+        // Only one word left, you either guess it or you don't
+        return if guess.is_candidate { 99 } else { 0 };
+    }
+    (99.0 * guess.entropy / best.entropy).round().min(99.0) as u32
+}
+
+fn luck_score(guess: &PreparsedWord, candidates: &[&PreparsedWord], wordleword: [char; 5]) -> u32 {
+    let buckets = pattern_buckets(guess, candidates);
+    // Getting it right leaves nothing to guess
+    let words_left = |code: usize| {
+        if code == SOLVED_CODE {
+            0
+        } else {
+            buckets[code]
+        }
+    };
+    let actual = words_left(pattern_code(guess.word, wordleword));
+    let total = candidates.len() as f64;
+    let mut luck = 0.0;
+    for (code, count) in buckets.iter().enumerate().filter(|(_, count)| **count > 0) {
+        let p = *count as f64 / total;
+        match words_left(code).cmp(&actual) {
+            std::cmp::Ordering::Greater => luck += p,
+            std::cmp::Ordering::Equal => luck += p / 2.0,
+            std::cmp::Ordering::Less => {}
+        }
+    }
+    (99.0 * luck).round() as u32
+}
+
+// Taking in a list of wordle words (answer last), score the skill and luck of
+// each guess along with what the best guess would have been. None for rows
+// where no known word fits anymore.
+pub fn calculate_guess_scores(words: &[[char; 5]]) -> anyhow::Result<Vec<Option<GuessScore>>> {
+    let (wordleword, guesses) = words
+        .split_last()
+        .ok_or_else(|| anyhow::anyhow!("wordle words passed in!"))?;
+
+    let mut validator = Validator::new(*wordleword);
+
+    let mut scores = vec![];
+    for word in guesses {
+        let candidates = validator.candidates();
+        let score = best_guess(&validator).map(|best| {
+            let guess = PreparsedWord::new(*word);
+            GuessScore {
+                skill: skill_score(&rank_guess(&guess, &candidates), &best),
+                luck: luck_score(&guess, &candidates, *wordleword),
+                best,
+            }
+        });
+        scores.push(score);
+        validator.injest_word(*word);
+    }
+
+    Ok(scores)
+}
+
+// The average skill and luck over a game, if any rows could be scored.
+pub fn summarize_guess_scores(scores: &[Option<GuessScore>]) -> Option<(u32, u32)> {
+    let scored: Vec<_> = scores.iter().flatten().collect();
+    if scored.is_empty() {
+        return None;
+    }
+    let skill: u32 = scored.iter().map(|s| s.skill).sum();
+    let luck: u32 = scored.iter().map(|s| s.luck).sum();
+    let n = scored.len() as f64;
+    Some((
+        (skill as f64 / n).round() as u32,
+        (luck as f64 / n).round() as u32,
+    ))
+}
+
+// Given the answer and the colours of each row of a shared grid, count how many
+// words could have been guessed to get each row. Like calculate_word_possibilities
+// this gives back the count of regular answers and the count including extra words.
+pub fn calculate_pattern_possibilities(
+    wordleword: [char; 5],
+    rows: &[[Tile; 5]],
+) -> Vec<(u32, u32)> {
+    let mut valid_buckets = [0u32; 243];
+    for word in VALID_WORDS_CHARS.iter() {
+        valid_buckets[pattern_code(word.word, wordleword)] += 1;
+    }
+    let mut extra_buckets = [0u32; 243];
+    for word in EXTRA_WORDS_CHARS.iter() {
+        extra_buckets[pattern_code(word.word, wordleword)] += 1;
+    }
+    rows.iter()
+        .map(|row| {
+            let code = tiles_code(row);
+            (
+                valid_buckets[code],
+                valid_buckets[code] + extra_buckets[code],
+            )
+        })
+        .collect()
+}
+
 pub fn wordle_word_to_char_array(word: &str) -> anyhow::Result<[char; 5]> {
     if word.len() != 5 {
         return Err(anyhow::anyhow!(
@@ -188,6 +455,12 @@ pub fn wordle_word_to_char_array(word: &str) -> anyhow::Result<[char; 5]> {
             word
         ));
     }
+    if !word.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return Err(anyhow::anyhow!(
+            "word needs to be only letters, got {}",
+            word
+        ));
+    }
     let chars = word.to_lowercase();
     let mut arr = ['a'; 5];
     for (i, ch) in chars.chars().enumerate() {
@@ -198,7 +471,29 @@ pub fn wordle_word_to_char_array(word: &str) -> anyhow::Result<[char; 5]> {
 
 #[cfg(test)]
 mod tests {
-    use super::{calculate_word_possibilities, parse_words_list};
+    use super::{
+        best_guess, calculate_guess_scores, calculate_pattern_possibilities,
+        calculate_word_possibilities, parse_words_list, pattern_code, rank_guess, score_guess,
+        summarize_guess_scores, wordle_word_to_char_array, PreparsedWord, Tile, Validator,
+        DEFAULT_WORDS_TO_SHOW, EXTRA_WORDS_CHARS, VALID_WORDS_CHARS,
+    };
+
+    fn word(w: &str) -> [char; 5] {
+        wordle_word_to_char_array(w).unwrap()
+    }
+
+    // "g" is green, "y" is yellow, "." is grey
+    fn tiles(pattern: &str) -> [Tile; 5] {
+        let mut tiles = [Tile::Absent; 5];
+        for (i, ch) in pattern.chars().enumerate() {
+            tiles[i] = match ch {
+                'g' => Tile::Correct,
+                'y' => Tile::Present,
+                _ => Tile::Absent,
+            };
+        }
+        tiles
+    }
 
     #[test]
     fn test_calculate_word_possibilities() {
@@ -209,7 +504,245 @@ slide
 oxide",
         )
         .unwrap();
-        let pos = calculate_word_possibilities(&mut words).unwrap();
+        let pos = calculate_word_possibilities(&mut words, DEFAULT_WORDS_TO_SHOW).unwrap();
         println!("{:?}", pos)
     }
+
+    #[test]
+    fn test_score_guess() {
+        // (guess, answer, pattern)
+        let cases = [
+            ("speed", "abide", "..y.y"),
+            ("eerie", "there", "y.y.g"),
+            ("geese", "those", "...gg"),
+            ("llama", "hello", "yy..."),
+            ("hello", "llama", "..yy."),
+            ("sassy", "essay", "yyg.g"),
+            ("array", "rarer", "yyg.."),
+            ("mamma", "added", ".y..."),
+            ("added", "dread", "yy.yg"),
+            ("abide", "abide", "ggggg"),
+        ];
+        for (guess, answer, pattern) in cases.iter() {
+            assert_eq!(
+                score_guess(word(guess), word(answer)),
+                tiles(pattern),
+                "{} against {}",
+                guess,
+                answer
+            );
+        }
+    }
+
+    #[test]
+    fn test_validator_duplicate_letters() {
+        // (answer, guesses, still possible, ruled out)
+        type Case<'a> = (&'a str, &'a [&'a str], &'a [&'a str], &'a [&'a str]);
+        let cases: &[Case] = &[
+            // Only one e in the answer, so two e's is out
+            (
+                "abide",
+                &["speed"],
+                &["abide", "diode"],
+                &["exude", "eerie"],
+            ),
+            // The grey e caps it at two e's, the yellow e needs a second one
+            (
+                "there",
+                &["eerie"],
+                &["there", "where"],
+                &["rouse", "geese"],
+            ),
+            // Grey e's with green e's still allow exactly those e's
+            (
+                "those",
+                &["geese"],
+                &["those", "horse"],
+                &["eerie", "these"],
+            ),
+            // Both l's are yellow, so two l's are needed
+            ("hello", &["llama"], &["hello"], &["loyal", "light"]),
+            ("rarer", &["array"], &["rarer"], &["rayon", "racer"]),
+            (
+                "dread",
+                &["added", "eared"],
+                &["dread"],
+                &["adder", "dared"],
+            ),
+        ];
+        for (answer, guesses, included, excluded) in cases.iter() {
+            let mut validator = Validator::new(word(answer));
+            for guess in guesses.iter() {
+                validator.injest_word(word(guess));
+            }
+            for w in included.iter() {
+                assert!(
+                    validator.valid_for_word(&PreparsedWord::new(word(w))),
+                    "{} should be possible for {} after {:?}",
+                    w,
+                    answer,
+                    guesses
+                );
+            }
+            for w in excluded.iter() {
+                assert!(
+                    !validator.valid_for_word(&PreparsedWord::new(word(w))),
+                    "{} should be ruled out for {} after {:?}",
+                    w,
+                    answer,
+                    guesses
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_validator_matches_scoring() {
+        // A word is still possible exactly when it would have scored every
+        // guess the same way the real answer did.
+        let games: &[&[&str]] = &[
+            &["speed", "abide"],
+            &["eerie", "there"],
+            &["geese", "those"],
+            &["llama", "hello"],
+            &["sassy", "essay"],
+            &["array", "rarer"],
+            &["mamma", "added", "dread"],
+            &["train", "weigh", "slide", "oxide"],
+        ];
+        for game in games.iter() {
+            let answer = word(game[game.len() - 1]);
+            let mut validator = Validator::new(answer);
+            let mut guesses = vec![];
+            for guess in game[..game.len() - 1].iter() {
+                let guess = word(guess);
+                validator.injest_word(guess);
+                guesses.push(guess);
+                for candidate in VALID_WORDS_CHARS.iter().chain(EXTRA_WORDS_CHARS.iter()) {
+                    let expected = guesses
+                        .iter()
+                        .all(|g| score_guess(*g, candidate.word) == score_guess(*g, answer));
+                    assert_eq!(
+                        validator.valid_for_word(candidate),
+                        expected,
+                        "{} after {:?}",
+                        candidate.word.iter().collect::<String>(),
+                        game
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pattern_code() {
+        assert_eq!(pattern_code(word("abide"), word("abide")), 242);
+        assert_eq!(pattern_code(word("fuzzy"), word("abide")), 0);
+        // yellow e and d, grey everything else
+        assert_eq!(pattern_code(word("speed"), word("abide")), 9 + 1);
+    }
+
+    #[test]
+    fn test_best_guess() {
+        let mut validator = Validator::new(word("abide"));
+        validator.injest_word(word("speed"));
+        let candidates = validator.candidates();
+        let best = best_guess(&validator).unwrap();
+        assert!(best.expected_remaining <= candidates.len() as f64);
+        for guess in ["speed", "abide", "crane", "diode"].iter() {
+            let ranked = rank_guess(&PreparsedWord::new(word(guess)), &candidates);
+            assert!(
+                best.entropy >= ranked.entropy,
+                "{} beat {}",
+                guess,
+                best.word
+            );
+        }
+
+        // Once it's down to one word, just guess it
+        validator.injest_word(word("bride"));
+        validator.injest_word(word("aside"));
+        assert_eq!(validator.candidates().len(), 1);
+        let best = best_guess(&validator).unwrap();
+        assert_eq!(best.word, "abide");
+        assert!(best.is_candidate);
+        assert_eq!(best.expected_remaining, 1.0);
+
+        // Nothing fits a made up answer
+        let mut validator = Validator::new(word("qxzvj"));
+        validator.injest_word(word("qxzvj"));
+        assert!(best_guess(&validator).is_none());
+    }
+
+    #[test]
+    fn test_calculate_guess_scores() {
+        let words = parse_words_list(
+            "train
+weigh
+slide
+oxide",
+        )
+        .unwrap();
+        let scores = calculate_guess_scores(&words).unwrap();
+        assert_eq!(scores.len(), 3);
+        let opening = &scores[0].as_ref().unwrap().best;
+        assert!(opening.entropy > 5.0);
+        for score in scores.iter() {
+            let score = score.as_ref().unwrap();
+            assert!(score.skill <= 99);
+            assert!(score.luck <= 99);
+        }
+        let (skill, luck) = summarize_guess_scores(&scores).unwrap();
+        assert!(skill <= 99 && luck <= 99);
+    }
+
+    #[test]
+    fn test_guess_scores_extremes() {
+        // Playing the best opener is as skillful as it gets
+        let words = parse_words_list("soare\nabide").unwrap();
+        let scores = calculate_guess_scores(&words).unwrap();
+        assert_eq!(scores[0].as_ref().unwrap().skill, 99);
+
+        // With one word left, guessing anything else is a wasted row, and
+        // there's no luck involved either way
+        let words = parse_words_list("bride\naside\nfuzzy\nabide").unwrap();
+        let scores = calculate_guess_scores(&words).unwrap();
+        let last = scores[2].as_ref().unwrap();
+        assert_eq!(last.best.word, "abide");
+        assert_eq!(last.skill, 0);
+        assert_eq!(last.luck, 50);
+
+        // Getting it straight away is as lucky as it gets
+        let words = parse_words_list("crane\nabide\nabide").unwrap();
+        let scores = calculate_guess_scores(&words).unwrap();
+        assert!(scores[1].as_ref().unwrap().luck >= 98);
+
+        // Nothing to score against a made up word
+        let words = parse_words_list("qxzvj\nqxzvj\nqxzvj").unwrap();
+        let scores = calculate_guess_scores(&words).unwrap();
+        assert!(scores[0].is_some());
+        assert!(scores[1].is_none());
+        assert!(summarize_guess_scores(&[]).is_none());
+    }
+
+    #[test]
+    fn test_calculate_pattern_possibilities() {
+        let answer = word("abide");
+        let rows = [
+            score_guess(word("speed"), answer),
+            score_guess(word("bride"), answer),
+            tiles("ggggg"),
+        ];
+        let counts = calculate_pattern_possibilities(answer, &rows);
+        assert_eq!(counts.len(), 3);
+        for (valid, total) in counts.iter() {
+            assert!(valid <= total);
+            assert!(*total >= 1);
+        }
+        // Only the answer itself comes back all green
+        assert_eq!(counts[2], (1, 1));
+        // A pattern nothing can make
+        let counts = calculate_pattern_possibilities(answer, &[tiles("ggggy")]);
+        assert_eq!(counts, vec![(0, 0)]);
+    }
 }
@@ -0,0 +1,162 @@
+use serenity::{
+    http::{error::Error as HttpError, StatusCode},
+    model::ModelError,
+};
+
+// Discord's json error codes for when the bot can't see something or isn't
+// allowed to do it
+const MISSING_ACCESS: isize = 50001;
+const MISSING_PERMISSIONS: isize = 50013;
+
+// What went wrong handling a message, sorted by whether there's anything the
+// people in the channel can do about it.
+#[derive(Debug)]
+pub enum BotError {
+    // Discord said no. The permission's named when we know which call it was.
+    MissingPermission(Option<&'static str>),
+    // It only makes sense in a server, not a DM
+    NoGuild,
+    // Something in the message we couldn't use
+    Parse(String),
+    // Couldn't read the saved results
+    Store(anyhow::Error),
+    // Anything else from discord, like outages or rate limits
+    Http(serenity::Error),
+}
+
+impl BotError {
+    // What to tell the channel, when there's anything they can do about it
+    pub fn user_message(&self) -> Option<String> {
+        match self {
+            BotError::MissingPermission(Some(permission)) => {
+                Some(format!("I need {} permission here", permission))
+            }
+            BotError::MissingPermission(None) => {
+                Some("I'm missing a permission here, not sure which".to_string())
+            }
+            BotError::NoGuild => Some("That only works in a server, not in DMs.".to_string()),
+            BotError::Parse(reason) => Some(format!("Weird, {}", reason)),
+            BotError::Store(_) => {
+                Some("Weird, something went wrong looking up stats, not sure what".to_string())
+            }
+            BotError::Http(_) => None,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BotError::MissingPermission(_) => "permission",
+            BotError::NoGuild => "no_guild",
+            BotError::Parse(_) => "parse",
+            BotError::Store(_) => "store",
+            BotError::Http(_) => "http",
+        }
+    }
+
+    // One line per error, so they're easy to grep for
+    pub fn log(&self, during: &str) {
+        let detail = match self {
+            BotError::MissingPermission(permission) => permission.unwrap_or_default().to_string(),
+            BotError::NoGuild => String::new(),
+            BotError::Parse(reason) => reason.clone(),
+            BotError::Store(e) => e.to_string(),
+            BotError::Http(e) => e.to_string(),
+        };
+        println!(
+            "error kind={} during={} detail={:?}",
+            self.kind(),
+            during,
+            detail
+        );
+    }
+}
+
+fn is_permission_error(status: StatusCode, code: isize) -> bool {
+    status == StatusCode::FORBIDDEN || matches!(code, MISSING_ACCESS | MISSING_PERMISSIONS)
+}
+
+impl From<serenity::Error> for BotError {
+    fn from(e: serenity::Error) -> BotError {
+        let denied = match &e {
+            serenity::Error::Http(http) => match http.as_ref() {
+                HttpError::UnsuccessfulRequest(response) => {
+                    is_permission_error(response.status_code, response.error.code)
+                }
+                _ => false,
+            },
+            serenity::Error::Model(ModelError::InvalidPermissions(_)) => true,
+            _ => false,
+        };
+        if denied {
+            BotError::MissingPermission(None)
+        } else {
+            BotError::Http(e)
+        }
+    }
+}
+
+// For a discord call that needs a particular permission, so that's what
+// gets named if it's refused
+pub fn needs(permission: &'static str) -> impl FnOnce(serenity::Error) -> BotError {
+    move |e| match BotError::from(e) {
+        BotError::MissingPermission(_) => BotError::MissingPermission(Some(permission)),
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::{
+        http::{error::Error as HttpError, StatusCode},
+        model::{ModelError, Permissions},
+    };
+
+    use super::{is_permission_error, needs, BotError};
+
+    #[test]
+    fn test_is_permission_error() {
+        assert!(is_permission_error(StatusCode::FORBIDDEN, 50013));
+        assert!(is_permission_error(StatusCode::BAD_REQUEST, 50001));
+        assert!(!is_permission_error(StatusCode::TOO_MANY_REQUESTS, 0));
+        assert!(!is_permission_error(StatusCode::NOT_FOUND, 10003));
+    }
+
+    #[test]
+    fn test_from_serenity() {
+        let denied = serenity::Error::Model(ModelError::InvalidPermissions(
+            Permissions::CREATE_PRIVATE_THREADS,
+        ));
+        assert!(matches!(
+            BotError::from(denied),
+            BotError::MissingPermission(None)
+        ));
+        let denied = serenity::Error::Model(ModelError::InvalidPermissions(
+            Permissions::CREATE_PRIVATE_THREADS,
+        ));
+        assert!(matches!(
+            needs("Create Private Threads")(denied),
+            BotError::MissingPermission(Some("Create Private Threads"))
+        ));
+        let outage = serenity::Error::from(HttpError::InvalidScheme);
+        assert!(matches!(needs("Send Messages")(outage), BotError::Http(_)));
+    }
+
+    #[test]
+    fn test_user_message() {
+        assert_eq!(
+            BotError::MissingPermission(Some("Create Private Threads"))
+                .user_message()
+                .unwrap(),
+            "I need Create Private Threads permission here"
+        );
+        assert!(BotError::NoGuild.user_message().is_some());
+        assert_eq!(
+            BotError::Parse("that's not a real wordle".to_string())
+                .user_message()
+                .unwrap(),
+            "Weird, that's not a real wordle"
+        );
+        let outage = serenity::Error::from(HttpError::InvalidScheme);
+        assert_eq!(BotError::Http(outage).user_message(), None);
+    }
+}
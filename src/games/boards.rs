@@ -0,0 +1,101 @@
+// Quordle and Octordle shares start with a block of per board results, a
+// keycap number for how many guesses each board took or 🟥 if it wasn't solved,
+// e.g. "5️⃣4️⃣\n6️⃣🟥". After that there's usually the grids and a link.
+
+use super::{Outcome, Score};
+
+// One line of board results, None if the line is something else
+fn parse_board_line(line: &str) -> Option<Vec<Option<u32>>> {
+    let mut boards = vec![];
+    let mut chars = line
+        .chars()
+        .filter(|c| *c != '\u{fe0f}' && !c.is_whitespace());
+    while let Some(c) = chars.next() {
+        let board = match c {
+            '0'..='9' => match chars.next() {
+                // Combining keycap
+                Some('\u{20e3}') => Some(c.to_digit(10)?),
+                _ => return None,
+            },
+            '🔟' => Some(10),
+            '🕚' => Some(11),
+            '🕛' => Some(12),
+            '🕐' => Some(13),
+            '🟥' => None,
+            _ => return None,
+        };
+        boards.push(board);
+    }
+    if boards.is_empty() {
+        return None;
+    }
+    Some(boards)
+}
+
+// The result for each board in reading order, or None if the share doesn't
+// start with exactly `count` of them
+pub fn parse_boards(body: &str, count: usize) -> Option<Vec<Option<u32>>> {
+    let boards: Vec<_> = body.lines().map_while(parse_board_line).flatten().collect();
+    if boards.len() != count {
+        return None;
+    }
+    Some(boards)
+}
+
+pub fn parse_outcome(body: &str, count: usize) -> Outcome {
+    match parse_boards(body, count) {
+        Some(boards) => Outcome::Boards(boards),
+        None => Outcome::Unscored,
+    }
+}
+
+// Scores are the total guesses across every board, so there's no "out of"
+pub fn score_text(score: Score) -> String {
+    match score {
+        Score::Solved(total) => total.to_string(),
+        Score::Failed => "X".to_string(),
+        Score::Unscored => String::new(),
+    }
+}
+
+pub fn cheer(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Boards(boards) if boards.iter().any(Option::is_none) => "So close!",
+        _ => "Nice!",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_boards;
+
+    #[test]
+    fn test_parse_boards() {
+        assert_eq!(
+            parse_boards("5️⃣4️⃣\n6️⃣7️⃣", 4),
+            Some(vec![Some(5), Some(4), Some(6), Some(7)])
+        );
+        assert_eq!(
+            parse_boards("6️⃣🔟\n4️⃣9️⃣\n7️⃣🕛\n5️⃣🕚", 8),
+            Some(vec![
+                Some(6),
+                Some(10),
+                Some(4),
+                Some(9),
+                Some(7),
+                Some(12),
+                Some(5),
+                Some(11)
+            ])
+        );
+        assert_eq!(
+            parse_boards("9️⃣🟥\n🟥8️⃣\n⬜⬜🟩⬜⬜ 🟨⬜⬜⬜⬜\nquordle.com", 4),
+            Some(vec![Some(9), None, None, Some(8)])
+        );
+        // Missing a board, or not the board block at all
+        assert_eq!(parse_boards("5️⃣4️⃣\n6️⃣", 4), None);
+        assert_eq!(parse_boards("🟩🟩🟩🟩🟩", 4), None);
+        assert_eq!(parse_boards("54\n67", 4), None);
+        assert_eq!(parse_boards("", 4), None);
+    }
+}
@@ -0,0 +1,101 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{match_header, parse_day, Game, Outcome, Score, Share};
+
+pub struct Connections;
+
+const GROUPS: u32 = 4;
+// The fourth mistake ends the game
+const MAX_MISTAKES: u32 = 4;
+
+// One row of four squares per guess, all the same colour if it found a group.
+// Scored as the number of guesses it took to find every group.
+fn parse_rows(body: &str) -> Outcome {
+    let rows: Vec<Vec<char>> = body
+        .lines()
+        .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect())
+        .filter(|row: &Vec<char>| {
+            row.len() == GROUPS as usize
+                && row.iter().all(|c| matches!(c, '🟨' | '🟩' | '🟦' | '🟪'))
+        })
+        .collect();
+    let found = rows
+        .iter()
+        .filter(|row| row.iter().all(|c| *c == row[0]))
+        .count() as u32;
+    match rows.len() as u32 {
+        0 => Outcome::Unscored,
+        guesses if found == GROUPS => Outcome::Attempts(Some(guesses)),
+        _ => Outcome::Attempts(None),
+    }
+}
+
+impl Game for Connections {
+    fn name(&self) -> &'static str {
+        "Connections"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        GROUPS + MAX_MISTAKES - 1
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex =
+                Regex::new(r"^Connections\s*\nPuzzle #([\d,]+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_rows(body),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        match score {
+            Score::Solved(guesses) if guesses == GROUPS => "perfect".to_string(),
+            Score::Solved(guesses) if guesses == GROUPS + 1 => "1 mistake".to_string(),
+            Score::Solved(guesses) => format!("{} mistakes", guesses - GROUPS),
+            Score::Failed => "X".to_string(),
+            Score::Unscored => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Connections;
+    use crate::games::{Game, Outcome, Share};
+    use crate::store::Score;
+
+    #[test]
+    fn test_connections() {
+        assert_eq!(
+            Connections
+                .parse(include_str!("fixtures/connections.txt"))
+                .unwrap(),
+            Share {
+                day: 34,
+                outcome: Outcome::Attempts(Some(5)),
+                hard_mode: false,
+                theme: None,
+                body: "🟨🟨🟨🟨\n🟩🟦🟩🟩\n🟩🟩🟩🟩\n🟦🟦🟦🟦\n🟪🟪🟪🟪"
+            }
+        );
+        assert_eq!(
+            Connections
+                .parse("Connections\nPuzzle #34\n🟨🟨🟨🟨\n🟩🟦🟩🟩\n🟩🟦🟩🟪\n🟩🟦🟦🟩\n🟪🟦🟩🟩")
+                .unwrap()
+                .outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Connections.parse("Connections are fun").is_none());
+        assert_eq!(Connections.score_text(Score::Solved(4)), "perfect");
+        assert_eq!(Connections.score_text(Score::Solved(5)), "1 mistake");
+        assert_eq!(Connections.score_text(Score::Solved(7)), "3 mistakes");
+    }
+}
@@ -0,0 +1,57 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_attempts, parse_day, Game, Share};
+
+pub struct Costcodle;
+
+impl Game for Costcodle {
+    fn name(&self) -> &'static str {
+        "Costcodle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^Costcodle #([\d,]+) ([\dX])/6").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_attempts(captures.get(2)?.as_str())?,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Costcodle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_costcodle() {
+        assert_eq!(
+            Costcodle
+                .parse(include_str!("fixtures/costcodle.txt"))
+                .unwrap(),
+            Share {
+                day: 52,
+                outcome: Outcome::Attempts(Some(3)),
+                hard_mode: false,
+                theme: None,
+                body: "⬜🟨⬜⬆️⬜\n🟩🟨⬜⬇️⬜\n🟩🟩🟩🟩🟩\nhttps://costcodle.com"
+            }
+        );
+        assert_eq!(
+            Costcodle.parse("Costcodle #52 X/6").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Costcodle.parse("Costcodle 52").is_none());
+    }
+}
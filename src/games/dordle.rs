@@ -0,0 +1,81 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{boards, match_header, parse_day, Game, Outcome, Score, Share};
+
+pub struct Dordle;
+
+const BOARDS: u32 = 2;
+
+// Two boards at once, the score is each board's guesses like "5&6/7"
+fn parse_board(result: &str) -> Option<Option<u32>> {
+    match result {
+        "X" => Some(None),
+        _ => Some(Some(result.parse().ok()?)),
+    }
+}
+
+impl Game for Dordle {
+    fn name(&self) -> &'static str {
+        "Dordle"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        7
+    }
+
+    fn failed_score(&self) -> f64 {
+        (BOARDS * self.max_attempts() + 1) as f64
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex =
+                Regex::new(r"^Dordle #([\d,]+) ([\dX])&([\dX])/7").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: Outcome::Boards(vec![
+                parse_board(captures.get(2)?.as_str())?,
+                parse_board(captures.get(3)?.as_str())?,
+            ]),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        boards::score_text(score)
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        boards::cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dordle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_dordle() {
+        assert_eq!(
+            Dordle.parse(include_str!("fixtures/dordle.txt")).unwrap(),
+            Share {
+                day: 123,
+                outcome: Outcome::Boards(vec![Some(5), Some(6)]),
+                hard_mode: false,
+            theme: None,
+                body: "⬜🟨⬜⬜⬜ ⬜⬜⬜🟨⬜\n🟩🟨⬜⬜🟩 ⬜🟩⬜🟨⬜\n🟩🟩🟩🟩🟩 🟩🟩⬜🟨⬜\n⬛⬛⬛⬛⬛ 🟩🟩🟩🟩🟩\nzaratustra.itch.io/dordle"
+            }
+        );
+        assert_eq!(
+            Dordle.parse("Dordle #0123 X&6/7").unwrap().outcome,
+            Outcome::Boards(vec![None, Some(6)])
+        );
+        assert!(Dordle.parse("Dordle #0123 5/7").is_none());
+    }
+}
@@ -0,0 +1,62 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_day, tries_outcome, Game, Share};
+
+pub struct Framed;
+
+// One square per try after the 🎥, 🟥 for a wrong guess, 🟩 for the right one
+// and ⬛ for the tries that weren't needed.
+const MISSES: &[char] = &['🟥'];
+
+impl Game for Framed {
+    fn name(&self) -> &'static str {
+        "Framed"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^Framed #([\d,]+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: tries_outcome(body, MISSES, self.max_attempts()),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Framed;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_framed() {
+        assert_eq!(
+            Framed.parse(include_str!("fixtures/framed.txt")).unwrap(),
+            Share {
+                day: 95,
+                outcome: Outcome::Attempts(Some(3)),
+                hard_mode: false,
+                theme: None,
+                body: "🎥 🟥 🟥 🟩 ⬛ ⬛ ⬛\n\nhttps://framed.wtf"
+            }
+        );
+        assert_eq!(
+            Framed
+                .parse("Framed #95\n🎥 🟥 🟥 🟥 🟥 🟥 🟥")
+                .unwrap()
+                .outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Framed.parse("Framed").is_none());
+    }
+}
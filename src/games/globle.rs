@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{match_header, Game, Outcome, Score, Share};
+
+pub struct Globle;
+
+// Globle shares have a date instead of a puzzle number, so days get counted
+// from the start of 2022, around when it came out.
+fn day_number(date: &str) -> Option<u32> {
+    let date = NaiveDate::parse_from_str(date, "%b %d, %Y").ok()?;
+    let first = NaiveDate::from_ymd_opt(2022, 1, 1)?;
+    (date - first).num_days().try_into().ok()
+}
+
+impl Game for Globle {
+    fn name(&self) -> &'static str {
+        "Globle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^🌎 (\w{3} \d{1,2}, \d{4}) 🌍").unwrap();
+            // You keep guessing countries until you get it, so there's no fail
+            static ref GUESSES_REG: Regex = Regex::new(r"= (\d+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        let outcome = match GUESSES_REG.captures(body) {
+            Some(guesses) => Outcome::Attempts(Some(guesses.get(1)?.as_str().parse().ok()?)),
+            None => Outcome::Unscored,
+        };
+        Some(Share {
+            day: day_number(captures.get(1)?.as_str())?,
+            outcome,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        match score {
+            Score::Solved(guesses) => format!("{} guesses", guesses),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{day_number, Globle};
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_globle() {
+        assert_eq!(
+            Globle.parse(include_str!("fixtures/globle.txt")).unwrap(),
+            Share {
+                day: 103,
+                outcome: Outcome::Attempts(Some(4)),
+                hard_mode: false,
+                theme: None,
+                body: "🔥 2 | Avg. Guesses: 8.5\n🟨🟧🟥🟩 = 4\n\nhttps://globle-game.com\n#globle"
+            }
+        );
+        assert!(Globle.parse("🌎 April 14th 🌍").is_none());
+    }
+
+    #[test]
+    fn test_day_number() {
+        assert_eq!(day_number("Jan 1, 2022"), Some(0));
+        assert_eq!(day_number("Jan 1, 2023"), Some(365));
+        assert_eq!(day_number("Dec 31, 2021"), None);
+    }
+}
@@ -0,0 +1,76 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_day, tries_outcome, Game, Share};
+
+pub struct Heardle;
+
+// One square per try, 🟥 for a wrong guess, ⬛ for a skip, 🟩 for the right
+// one and ⬜ for the tries that weren't needed.
+const MISSES: &[char] = &['🟥', '⬛'];
+
+impl Game for Heardle {
+    fn name(&self) -> &'static str {
+        "Heardle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^#?Heardle #?([\d,]+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: tries_outcome(body, MISSES, self.max_attempts()),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Heardle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_heardle() {
+        let share = Share {
+            day: 16,
+            outcome: Outcome::Unscored,
+            hard_mode: false,
+            theme: None,
+            body: "",
+        };
+        assert_eq!(Heardle.parse("#Heardle #16").unwrap(), share);
+        assert_eq!(Heardle.parse("Heardle 16").unwrap(), share);
+        assert_eq!(
+            Heardle
+                .parse(
+                    "#Heardle #16
+
+🔈🟥⬛️⬛️🟩⬜️⬜️"
+                )
+                .unwrap(),
+            Share {
+                day: 16,
+                outcome: Outcome::Attempts(Some(4)),
+                hard_mode: false,
+                theme: None,
+                body: "🔈🟥⬛️⬛️🟩⬜️⬜️"
+            }
+        );
+        assert_eq!(
+            Heardle
+                .parse("#Heardle #16\n\n🔇🟥🟥⬛️🟥🟥🟥")
+                .unwrap()
+                .outcome,
+            Outcome::Attempts(None)
+        );
+    }
+}
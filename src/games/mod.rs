@@ -0,0 +1,304 @@
+use regex::{Captures, Regex};
+use serenity::model::misc::Mention;
+
+use crate::grid::Theme;
+use crate::store::Score;
+
+mod boards;
+mod connections;
+mod costcodle;
+mod dordle;
+mod framed;
+mod globle;
+mod heardle;
+mod nerdle;
+mod octordle;
+mod quordle;
+mod redactle;
+mod squardle;
+mod tradle;
+mod waffle;
+mod wordle;
+mod worldle;
+
+// Every game we make solver threads for. Adding a game means adding a module
+// and putting it in here.
+pub static GAMES: &[&dyn Game] = &[
+    &wordle::Wordle,
+    &heardle::Heardle,
+    &tradle::Tradle,
+    &quordle::Quordle,
+    &octordle::Octordle,
+    &redactle::Redactle,
+    &nerdle::Nerdle,
+    &worldle::Worldle,
+    &globle::Globle,
+    &connections::Connections,
+    &framed::Framed,
+    &costcodle::Costcodle,
+    &dordle::Dordle,
+    &waffle::Waffle,
+    &squardle::Squardle,
+];
+
+// How someone did, as far as the share text says
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    // Solved in this many attempts, or None if they ran out
+    Attempts(Option<u32>),
+    // Quordle style, how many guesses each board took or None if it wasn't solved
+    Boards(Vec<Option<u32>>),
+    // Games like Redactle where you keep guessing until you get it
+    Accuracy { guesses: u32, accuracy: f64 },
+    // Nothing in the share we can score
+    Unscored,
+}
+
+// A result someone posted
+#[derive(Clone, Debug, PartialEq)]
+pub struct Share<'a> {
+    pub day: u32,
+    pub outcome: Outcome,
+    pub hard_mode: bool,
+    // For games with a wordle style grid
+    pub theme: Option<Theme>,
+    // Whatever came after the result line, usually the emoji grid
+    pub body: &'a str,
+}
+
+impl Share<'_> {
+    pub fn score(&self) -> Score {
+        match self.outcome {
+            Outcome::Attempts(Some(attempts)) => Score::Solved(attempts),
+            Outcome::Attempts(None) => Score::Failed,
+            // The total only counts if every board got solved
+            Outcome::Boards(ref boards) => match boards.iter().copied().sum::<Option<u32>>() {
+                Some(total) => Score::Solved(total),
+                None => Score::Failed,
+            },
+            Outcome::Accuracy { .. } | Outcome::Unscored => Score::Unscored,
+        }
+    }
+}
+
+pub trait Game: Sync {
+    fn name(&self) -> &'static str;
+
+    // None if the message isn't a share from this game
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>>;
+
+    fn max_attempts(&self) -> u32 {
+        6
+    }
+
+    // What a fail counts as when averaging scores, one worse than the worst
+    // possible solve
+    fn failed_score(&self) -> f64 {
+        (self.max_attempts() + 1) as f64
+    }
+
+    // How a recorded score reads, e.g. "3/6"
+    fn score_text(&self, score: Score) -> String {
+        match score {
+            Score::Solved(attempts) => format!("{}/{}", attempts, self.max_attempts()),
+            Score::Failed => format!("X/{}", self.max_attempts()),
+            Score::Unscored => String::new(),
+        }
+    }
+
+    // How the result shows up in the welcome message
+    fn result_text(&self, share: &Share) -> String {
+        match share.outcome {
+            Outcome::Attempts(_) => self.score_text(share.score()),
+            Outcome::Boards(ref boards) => {
+                let solved = boards.iter().filter(|b| b.is_some()).count();
+                match share.score() {
+                    Score::Solved(total) => format!("solved all {} in {}", boards.len(), total),
+                    _ => format!("solved {} of {}", solved, boards.len()),
+                }
+            }
+            Outcome::Accuracy { guesses, accuracy } => {
+                format!("{} guesses at {:.2}% accuracy", guesses, accuracy)
+            }
+            Outcome::Unscored => String::new(),
+        }
+    }
+
+    fn cheer(&self, _share: &Share) -> &'static str {
+        "Nice!"
+    }
+}
+
+// Finds the game a message is a share of
+pub fn parse_share(content: &str) -> Option<(&'static dyn Game, Share<'_>)> {
+    GAMES
+        .iter()
+        .find_map(|game| Some((*game, game.parse(content)?)))
+}
+
+pub fn find_game(name: &str) -> Option<&'static dyn Game> {
+    GAMES
+        .iter()
+        .find(|game| game.name().eq_ignore_ascii_case(name))
+        .copied()
+}
+
+// Results can be from games we don't parse any more, those get treated like wordle
+pub fn game_or_default(name: &str) -> &'static dyn Game {
+    find_game(name).unwrap_or(GAMES[0])
+}
+
+pub fn welcome_message(game: &dyn Game, share: &Share, author: Mention) -> String {
+    let mut msg = format!(
+        "Welcome to the secret {} club {}\n{}\n{} {}",
+        game.name(),
+        author,
+        share.body,
+        game.result_text(share),
+        game.cheer(share)
+    );
+    if share.hard_mode && share.score() != Score::Failed {
+        msg.push_str(" And on hard mode!");
+    }
+    msg
+}
+
+// Matches `header` at the start of the message, everything after it is the body
+fn match_header<'a>(header: &Regex, content: &'a str) -> Option<(Captures<'a>, &'a str)> {
+    let captures = header.captures(content)?;
+    let body = content[captures.get(0)?.end()..].trim();
+    Some((captures, body))
+}
+
+// Day numbers get big enough for thousands separators, e.g. "Wordle 1,234"
+pub fn parse_day(day: &str) -> Option<u32> {
+    day.replace(',', "").parse().ok()
+}
+
+// "3" or "X" out of a "3/6" style score
+fn parse_attempts(result: &str) -> Option<Outcome> {
+    match result {
+        "X" => Some(Outcome::Attempts(None)),
+        _ => Some(Outcome::Attempts(Some(result.parse().ok()?))),
+    }
+}
+
+// For games that show a square per try, which one is the 🟩, or a fail if all
+// `max` tries got used up without one. `misses` are the squares for used tries.
+fn tries_outcome(body: &str, misses: &[char], max: u32) -> Outcome {
+    let tries: Vec<char> = body
+        .chars()
+        .filter(|c| *c == '🟩' || misses.contains(c))
+        .collect();
+    match tries.iter().position(|c| *c == '🟩') {
+        Some(i) => Outcome::Attempts(Some(i as u32 + 1)),
+        None if tries.len() as u32 >= max => Outcome::Attempts(None),
+        None => Outcome::Unscored,
+    }
+}
+
+// For games scored out of six tries
+fn attempts_cheer(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Attempts(Some(1)) => "WTFFF?!?!?!",
+        Outcome::Attempts(Some(2)) => "Master! You're a master!",
+        Outcome::Attempts(Some(5)) => "Just made it!",
+        Outcome::Attempts(Some(6)) => "Phew! That was a close one!",
+        Outcome::Attempts(None) => "Nutz! Better luck next time!",
+        _ => "Nice! You got it!",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{game_or_default, parse_day, parse_share, welcome_message, GAMES};
+    use crate::store::Score;
+    use serenity::model::id::UserId;
+
+    #[test]
+    fn test_parse_share() {
+        let (game, share) = parse_share("Wordle 229 6/6\n🟩🟩🟩🟩🟩").unwrap();
+        assert_eq!(game.name(), "Wordle");
+        assert_eq!(share.day, 229);
+        assert_eq!(share.score(), Score::Solved(6));
+        let (game, share) = parse_share("Daily Quordle 50\n5️⃣4️⃣\n6️⃣7️⃣").unwrap();
+        assert_eq!(game.name(), "Quordle");
+        assert_eq!(share.score(), Score::Solved(22));
+        let (_, share) = parse_share("Daily Quordle 50\n5️⃣🟥\n6️⃣7️⃣").unwrap();
+        assert_eq!(share.score(), Score::Failed);
+        let (_, share) = parse_share("Daily Quordle 50").unwrap();
+        assert_eq!(share.score(), Score::Unscored);
+        assert!(parse_share("hello 5").is_none());
+        assert!(parse_share("Wordle is fun").is_none());
+    }
+
+    #[test]
+    fn test_game_names_are_unique() {
+        for (i, game) in GAMES.iter().enumerate() {
+            assert!(GAMES[..i].iter().all(|g| g.name() != game.name()));
+        }
+    }
+
+    #[test]
+    fn test_score_text() {
+        let wordle = game_or_default("wordle");
+        assert_eq!(wordle.score_text(Score::Solved(3)), "3/6");
+        assert_eq!(wordle.score_text(Score::Failed), "X/6");
+        assert_eq!(wordle.failed_score(), 7.0);
+        assert_eq!(game_or_default("Nobody plays this").name(), "Wordle");
+        let quordle = game_or_default("Quordle");
+        assert_eq!(quordle.score_text(Score::Solved(22)), "22");
+        assert_eq!(quordle.score_text(Score::Failed), "X");
+        assert_eq!(quordle.failed_score(), 37.0);
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(parse_day("229"), Some(229));
+        assert_eq!(parse_day("1,234"), Some(1234));
+        assert_eq!(parse_day("abc"), None);
+    }
+
+    #[test]
+    fn test_welcome_message() {
+        let author = UserId(1).into();
+        let (game, share) = parse_share("Wordle 229 6/6\n🟩🟩🟩🟩🟩").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Wordle club <@1>\n🟩🟩🟩🟩🟩\n6/6 Phew! That was a close one!"
+        );
+        let (game, share) = parse_share("Wordle 229 3/6*\n⬛🟨🟨⬛⬛\n🟩🟩🟩🟩🟩").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Wordle club <@1>\n⬛🟨🟨⬛⬛\n🟩🟩🟩🟩🟩\n3/6* Nice! You got it! And on hard mode!"
+        );
+        let (game, share) = parse_share("Wordle 229 X/6*").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Wordle club <@1>\n\nX/6* Nutz! Better luck next time!"
+        );
+        let (game, share) = parse_share("#Heardle #16\n\n🔈🟥⬛️🟩⬜️⬜️⬜️").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Heardle club <@1>\n🔈🟥⬛️🟩⬜️⬜️⬜️\n3/6 Nice! You got it!"
+        );
+        let (game, share) = parse_share("Daily Octordle #50\n6️⃣🔟\n4️⃣9️⃣\n7️⃣🕛\n5️⃣🕚").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Octordle club <@1>\n6️⃣🔟\n4️⃣9️⃣\n7️⃣🕛\n5️⃣🕚\nsolved all 8 in 64 Nice!"
+        );
+        let (game, share) = parse_share("Daily Quordle 50\n5️⃣🟥\n6️⃣7️⃣").unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Quordle club <@1>\n5️⃣🟥\n6️⃣7️⃣\nsolved 3 of 4 So close!"
+        );
+        let (game, share) = parse_share(
+            "I solved today's Redactle (#31) in 159 guesses with an accuracy of 28.30%.",
+        )
+        .unwrap();
+        assert_eq!(
+            welcome_message(game, &share, author),
+            "Welcome to the secret Redactle club <@1>\n\n159 guesses at 28.30% accuracy Nice!"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_attempts, parse_day, Game, Share};
+
+pub struct Nerdle;
+
+impl Game for Nerdle {
+    fn name(&self) -> &'static str {
+        "Nerdle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            // Nerdle calls itself nerdlegame when sharing
+            static ref HEADER_REG: Regex = Regex::new(r"^nerdlegame ([\d,]+) ([\dX])/6").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_attempts(captures.get(2)?.as_str())?,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Nerdle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_nerdle() {
+        assert_eq!(
+            Nerdle.parse(include_str!("fixtures/nerdle.txt")).unwrap(),
+            Share {
+                day: 51,
+                outcome: Outcome::Attempts(Some(3)),
+                hard_mode: false,
+                theme: None,
+                body:
+                    "🟪🟩⬛🟪🟪⬛🟪🟪\n🟩🟩🟪⬛🟩🟩🟪⬛\n🟩🟩🟩🟩🟩🟩🟩🟩\n\nnerdlegame.com #nerdle"
+            }
+        );
+        assert_eq!(
+            Nerdle.parse("nerdlegame 51 X/6").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Nerdle.parse("Wordle 51 3/6").is_none());
+    }
+}
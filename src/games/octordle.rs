@@ -0,0 +1,97 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{boards, match_header, parse_day, Game, Score, Share};
+
+const BOARDS: u32 = 8;
+
+pub struct Octordle;
+
+impl Game for Octordle {
+    fn name(&self) -> &'static str {
+        "Octordle"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        13
+    }
+
+    fn failed_score(&self) -> f64 {
+        (BOARDS * self.max_attempts() + 1) as f64
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        boards::score_text(score)
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        boards::cheer(&share.outcome)
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^Daily Octordle #?([\d,]+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: boards::parse_outcome(body, BOARDS as usize),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Octordle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_octordle() {
+        let share = Share {
+            day: 50,
+            outcome: Outcome::Boards(vec![
+                Some(6),
+                Some(10),
+                Some(4),
+                Some(9),
+                Some(7),
+                Some(12),
+                Some(5),
+                Some(11),
+            ]),
+            hard_mode: false,
+            theme: None,
+            body: "6️⃣🔟
+4️⃣9️⃣
+7️⃣🕛
+5️⃣🕚",
+        };
+        assert_eq!(
+            Octordle
+                .parse(
+                    "Daily Octordle 50
+6️⃣🔟
+4️⃣9️⃣
+7️⃣🕛
+5️⃣🕚"
+                )
+                .unwrap(),
+            share
+        );
+        assert_eq!(
+            Octordle
+                .parse(
+                    "Daily Octordle #50
+6️⃣🔟
+4️⃣9️⃣
+7️⃣🕛
+5️⃣🕚"
+                )
+                .unwrap(),
+            share
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{boards, match_header, parse_day, Game, Score, Share};
+
+const BOARDS: u32 = 4;
+
+pub struct Quordle;
+
+impl Game for Quordle {
+    fn name(&self) -> &'static str {
+        "Quordle"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        9
+    }
+
+    fn failed_score(&self) -> f64 {
+        (BOARDS * self.max_attempts() + 1) as f64
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        boards::score_text(score)
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        boards::cheer(&share.outcome)
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^Daily Quordle #?([\d,]+)").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: boards::parse_outcome(body, BOARDS as usize),
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Quordle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_quordle() {
+        let share = Share {
+            day: 50,
+            outcome: Outcome::Boards(vec![Some(5), Some(4), Some(6), Some(7)]),
+            hard_mode: false,
+            theme: None,
+            body: "5️⃣4️⃣
+6️⃣7️⃣",
+        };
+        assert_eq!(
+            Quordle
+                .parse(
+                    "Daily Quordle #50
+5️⃣4️⃣
+6️⃣7️⃣"
+                )
+                .unwrap(),
+            share
+        );
+        assert_eq!(
+            Quordle
+                .parse(
+                    "Daily Quordle 50
+5️⃣4️⃣
+6️⃣7️⃣"
+                )
+                .unwrap(),
+            share
+        );
+        assert_eq!(
+            Quordle
+                .parse(
+                    "Daily Quordle 50
+🟥4️⃣
+6️⃣🟥
+quordle.com"
+                )
+                .unwrap()
+                .outcome,
+            Outcome::Boards(vec![None, Some(4), Some(6), None])
+        );
+        assert!(Quordle.parse("Daily Octordle 50").is_none());
+    }
+}
@@ -0,0 +1,59 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{match_header, parse_day, Game, Outcome, Share};
+
+pub struct Redactle;
+
+impl Game for Redactle {
+    fn name(&self) -> &'static str {
+        "Redactle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(
+                r"^I solved today's Redactle \(#([\d,]+)\) in (\d+) guesses with an accuracy of ([\d.]+)%\.?"
+            )
+            .unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: Outcome::Accuracy {
+                guesses: captures.get(2)?.as_str().parse().ok()?,
+                accuracy: captures.get(3)?.as_str().parse().ok()?,
+            },
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Redactle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_redactle() {
+        assert_eq!(
+            Redactle.parse(
+                "I solved today's Redactle (#31) in 159 guesses with an accuracy of 28.30%. Played at https://www.redactle.com/"
+            )
+            .unwrap(),
+            Share {
+                day: 31,
+                outcome: Outcome::Accuracy {
+                    guesses: 159,
+                    accuracy: 28.3
+                },
+                hard_mode: false,
+            theme: None,
+                body: "Played at https://www.redactle.com/"
+            }
+        );
+        assert!(Redactle.parse("I solved today's Redactle (#31)").is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{match_header, parse_day, Game, Outcome, Score, Share};
+
+pub struct Squardle;
+
+// Squardle gives you 21 guesses for the whole square and shares how many
+// were left over
+const GUESSES: u32 = 21;
+
+impl Game for Squardle {
+    fn name(&self) -> &'static str {
+        "Squardle"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        GUESSES
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(
+                r"^I (solved|failed) Daily Squardle #([\d,]+)(?: with (\d+) guesses? to spare)?!?"
+            )
+            .unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        let outcome = match (captures.get(1)?.as_str(), captures.get(3)) {
+            ("failed", _) => Outcome::Attempts(None),
+            (_, Some(spare)) => {
+                Outcome::Attempts(Some(GUESSES.saturating_sub(spare.as_str().parse().ok()?)))
+            }
+            _ => Outcome::Unscored,
+        };
+        Some(Share {
+            day: parse_day(captures.get(2)?.as_str())?,
+            outcome,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        match score {
+            Score::Solved(guesses) => format!("{} to spare", GUESSES.saturating_sub(guesses)),
+            Score::Failed => "X".to_string(),
+            Score::Unscored => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Squardle;
+    use crate::games::{Game, Outcome, Share};
+    use crate::store::Score;
+
+    #[test]
+    fn test_squardle() {
+        assert_eq!(
+            Squardle
+                .parse(include_str!("fixtures/squardle.txt"))
+                .unwrap(),
+            Share {
+                day: 147,
+                outcome: Outcome::Attempts(Some(17)),
+                hard_mode: false,
+                theme: None,
+                body: "🟩🟩🟩🟩🟩\n🟩⬜🟩⬜🟩\n🟩🟩🟩🟩🟩\n🟩⬜🟩⬜🟩\n🟩🟩🟩🟩🟩\n\nsquardle.fun"
+            }
+        );
+        assert_eq!(
+            Squardle
+                .parse("I failed Daily Squardle #147")
+                .unwrap()
+                .outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Squardle.parse("I solved Daily Quordle #147").is_none());
+        assert_eq!(Squardle.score_text(Score::Solved(17)), "4 to spare");
+    }
+}
@@ -0,0 +1,65 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_attempts, parse_day, Game, Share};
+
+pub struct Tradle;
+
+impl Game for Tradle {
+    fn name(&self) -> &'static str {
+        "Tradle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^#?Tradle #?([\d,]+) ([\dX])/6").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_attempts(captures.get(2)?.as_str())?,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tradle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_tradle() {
+        let share = Share {
+            day: 7,
+            outcome: Outcome::Attempts(Some(1)),
+            hard_mode: false,
+            theme: None,
+            body: "",
+        };
+        assert_eq!(Tradle.parse("#Tradle #7 1/6").unwrap(), share);
+        assert_eq!(Tradle.parse("Tradle 7 1/6").unwrap(), share);
+        assert_eq!(
+            Tradle
+                .parse(
+                    "#Tradle #7 1/6
+🟩🟩🟩🟩🟩
+https://oec.world/en/tradle"
+                )
+                .unwrap()
+                .body,
+            "🟩🟩🟩🟩🟩
+https://oec.world/en/tradle"
+        );
+        assert_eq!(
+            Tradle.parse("#Tradle #7 X/6").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+    }
+}
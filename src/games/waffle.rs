@@ -0,0 +1,83 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{match_header, parse_day, Game, Outcome, Score, Share};
+
+pub struct Waffle;
+
+// You get 15 swaps and a perfect solve takes 10, the score is how many stars
+// (swaps) were left over. Stored as swaps used so lower is better like
+// everything else.
+const SWAPS: u32 = 15;
+const STARS: u32 = 5;
+
+impl Game for Waffle {
+    fn name(&self) -> &'static str {
+        "Waffle"
+    }
+
+    fn max_attempts(&self) -> u32 {
+        SWAPS
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            static ref HEADER_REG: Regex = Regex::new(r"^#waffle([\d,]+) ([\dX])/5").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        let outcome = match captures.get(2)?.as_str() {
+            "X" => Outcome::Attempts(None),
+            stars => Outcome::Attempts(Some(SWAPS - stars.parse::<u32>().ok()?.min(STARS))),
+        };
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn score_text(&self, score: Score) -> String {
+        match score {
+            Score::Solved(swaps) => format!("{}/{}", SWAPS.saturating_sub(swaps), STARS),
+            Score::Failed => format!("X/{}", STARS),
+            Score::Unscored => String::new(),
+        }
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        match share.outcome {
+            Outcome::Attempts(Some(swaps)) if swaps == SWAPS - STARS => "A perfect waffle!",
+            Outcome::Attempts(None) => "Soggy! Better luck next time!",
+            _ => "Nice!",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Waffle;
+    use crate::games::{Game, Outcome, Share};
+    use crate::store::Score;
+
+    #[test]
+    fn test_waffle() {
+        assert_eq!(
+            Waffle.parse(include_str!("fixtures/waffle.txt")).unwrap(),
+            Share {
+                day: 123,
+                outcome: Outcome::Attempts(Some(11)),
+                hard_mode: false,
+            theme: None,
+                body: "🟩🟩🟩🟩🟩\n🟩⭐️🟩⭐️🟩\n🟩🟩⭐️🟩🟩\n🟩⬜🟩⭐️🟩\n🟩🟩🟩🟩🟩\n\n🔥 streak: 5\nwafflegame.net"
+            }
+        );
+        assert_eq!(
+            Waffle.parse("#waffle123 X/5").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Waffle.parse("#waffle 4/5").is_none());
+        assert_eq!(Waffle.score_text(Score::Solved(11)), "4/5");
+    }
+}
@@ -0,0 +1,128 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_attempts, parse_day, Game, Share};
+use crate::grid::detect_theme;
+
+pub struct Wordle;
+
+impl Game for Wordle {
+    fn name(&self) -> &'static str {
+        "Wordle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            // Hard mode solves get a * after the score, e.g. "Wordle 200 3/6*"
+            static ref HEADER_REG: Regex = Regex::new(r"^Wordle ([\d,]+) ([\dX])/6(\*)?").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_attempts(captures.get(2)?.as_str())?,
+            hard_mode: captures.get(3).is_some(),
+            theme: if body.is_empty() {
+                None
+            } else {
+                Some(detect_theme(body))
+            },
+            body,
+        })
+    }
+
+    // Hard mode gets its * back
+    fn result_text(&self, share: &Share) -> String {
+        let result = self.score_text(share.score());
+        if share.hard_mode {
+            format!("{}*", result)
+        } else {
+            result
+        }
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wordle;
+    use crate::games::{Game, Outcome, Share};
+    use crate::grid::Theme;
+
+    #[test]
+    fn test_wordle() {
+        assert_eq!(
+            Wordle.parse("Wordle 1 1/6").unwrap(),
+            Share {
+                day: 1,
+                outcome: Outcome::Attempts(Some(1)),
+                hard_mode: false,
+                theme: None,
+                body: ""
+            }
+        );
+        assert_eq!(
+            Wordle.parse("Wordle 9 X/6").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+        assert_eq!(Wordle.parse("Wordle 1,234 3/6").unwrap().day, 1234);
+        assert_eq!(
+            Wordle
+                .parse(
+                    "Wordle 229 6/6
+⬛🟨🟨⬛⬛
+🟩⬛⬛⬛🟨
+🟩🟩⬛⬛⬛
+🟩🟩⬛⬛⬛
+🟩🟩⬛⬛🟨
+🟩🟩🟩🟩🟩
+"
+                )
+                .unwrap(),
+            Share {
+                day: 229,
+                outcome: Outcome::Attempts(Some(6)),
+                hard_mode: false,
+                theme: Some(Theme::default()),
+                body: "⬛🟨🟨⬛⬛
+🟩⬛⬛⬛🟨
+🟩🟩⬛⬛⬛
+🟩🟩⬛⬛⬛
+🟩🟩⬛⬛🟨
+🟩🟩🟩🟩🟩"
+            }
+        );
+        assert!(Wordle.parse("Tradle 7 1/6").is_none());
+        assert!(Wordle.parse("Wordle 229").is_none());
+    }
+
+    #[test]
+    fn test_theme() {
+        assert_eq!(
+            Wordle
+                .parse("Wordle 229 2/6\n⬜🟦⬜⬜🟧\n🟧🟧🟧🟧🟧")
+                .unwrap()
+                .theme,
+            Some(Theme {
+                light_mode: true,
+                high_contrast: true
+            })
+        );
+        assert_eq!(Wordle.parse("Wordle 229 2/6").unwrap().theme, None);
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        assert!(Wordle.parse("Wordle 200 3/6*").unwrap().hard_mode);
+        assert!(
+            Wordle
+                .parse("Wordle 200 X/6*\n⬛🟨🟨⬛⬛")
+                .unwrap()
+                .hard_mode
+        );
+        assert!(!Wordle.parse("Wordle 200 3/6").unwrap().hard_mode);
+        assert!(!Wordle.parse("Wordle 200 3/6\n*").unwrap().hard_mode);
+    }
+}
@@ -0,0 +1,56 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{attempts_cheer, match_header, parse_attempts, parse_day, Game, Share};
+
+pub struct Worldle;
+
+impl Game for Worldle {
+    fn name(&self) -> &'static str {
+        "Worldle"
+    }
+
+    fn parse<'a>(&self, content: &'a str) -> Option<Share<'a>> {
+        lazy_static! {
+            // The percentage is how close the last guess got
+            static ref HEADER_REG: Regex = Regex::new(r"^#Worldle #([\d,]+) ([\dX])/6(?: \(\d+%\))?").unwrap();
+        }
+        let (captures, body) = match_header(&HEADER_REG, content)?;
+        Some(Share {
+            day: parse_day(captures.get(1)?.as_str())?,
+            outcome: parse_attempts(captures.get(2)?.as_str())?,
+            hard_mode: false,
+            theme: None,
+            body,
+        })
+    }
+
+    fn cheer(&self, share: &Share) -> &'static str {
+        attempts_cheer(&share.outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Worldle;
+    use crate::games::{Game, Outcome, Share};
+
+    #[test]
+    fn test_worldle() {
+        assert_eq!(
+            Worldle.parse(include_str!("fixtures/worldle.txt")).unwrap(),
+            Share {
+                day: 60,
+                outcome: Outcome::Attempts(Some(4)),
+                hard_mode: false,
+            theme: None,
+                body: "🟩🟩🟩🟨⬜⬅️\n🟩🟩🟩🟩⬜↖️\n🟩🟩🟩🟩🟨⬆️\n🟩🟩🟩🟩🟩🎉\nhttps://worldle.teuteuf.fr"
+            }
+        );
+        assert_eq!(
+            Worldle.parse("#Worldle #60 X/6 (94%)").unwrap().outcome,
+            Outcome::Attempts(None)
+        );
+        assert!(Worldle.parse("Wordle 60 4/6").is_none());
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use serenity::model::{
+    channel::ChannelType,
+    id::{GuildId, RoleId, UserId},
+};
+
+use crate::error::BotError;
+use crate::platform::{ChatPlatform, Thread};
+use crate::threads::parse_solvers_thread_name;
+
+// How a guild keeps its solvers threads to people who've posted that day's
+// result.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateMode {
+    // Anyone can see the threads, the default
+    Open,
+    // Private threads, solvers get added as they post
+    Members,
+    // Threads in the <channel>_solvers channel, which only gets shown to that
+    // day's solvers role
+    Role,
+}
+
+impl GateMode {
+    pub fn parse(mode: &str) -> Option<GateMode> {
+        match mode.trim().to_lowercase().as_str() {
+            "open" => Some(GateMode::Open),
+            "members" => Some(GateMode::Members),
+            "role" => Some(GateMode::Role),
+            _ => None,
+        }
+    }
+}
+
+// Let someone who's posted their result into the thread for it
+pub async fn let_in(
+    platform: &dyn ChatPlatform,
+    guild_id: GuildId,
+    thread: &Thread,
+    user_id: UserId,
+) -> Result<(), BotError> {
+    if thread.kind == ChannelType::PrivateThread {
+        return platform.add_thread_member(thread.id, user_id).await;
+    }
+    let (game, day) = match parse_solvers_thread_name(&thread.name) {
+        Some(puzzle) => puzzle,
+        None => return Ok(()),
+    };
+    // The game's solvers roles by day
+    let roles: Vec<(RoleId, u32)> = platform
+        .roles(guild_id)
+        .await?
+        .into_iter()
+        .filter_map(|(role_id, name)| match parse_solvers_thread_name(&name) {
+            Some((role_game, role_day)) if role_game == game => Some((role_id, role_day)),
+            _ => None,
+        })
+        .collect();
+    // A role for a day that's been and gone would show them the newer
+    // thread too, so they've missed their chance
+    if roles.iter().any(|(_, role_day)| *role_day > day) {
+        return Ok(());
+    }
+    let role_id = match roles.iter().find(|(_, role_day)| *role_day == day) {
+        Some((role_id, _)) => *role_id,
+        None => create_role(platform, guild_id, thread, &roles).await?,
+    };
+    platform.add_member_role(guild_id, user_id, role_id).await
+}
+
+// The role for a thread is named after it and can see the channel it's in.
+// Only the newest day's solvers get to see it, so the roles from earlier days
+// go first.
+async fn create_role(
+    platform: &dyn ChatPlatform,
+    guild_id: GuildId,
+    thread: &Thread,
+    earlier: &[(RoleId, u32)],
+) -> Result<RoleId, BotError> {
+    for (role_id, _) in earlier {
+        platform.delete_role(guild_id, *role_id).await?;
+    }
+    let role_id = platform.create_role(guild_id, &thread.name).await?;
+    if let Some(channel_id) = thread.parent_id {
+        platform.show_channel_to_role(channel_id, role_id).await?;
+    }
+    // New day, new role, so the old ones might be done with
+    if let Err(e) = clean_up_roles(platform, guild_id).await {
+        e.log("roles");
+    }
+    Ok(role_id)
+}
+
+// Solvers roles go once their thread's archived, which takes their permission
+// on the channel with them.
+async fn clean_up_roles(platform: &dyn ChatPlatform, guild_id: GuildId) -> Result<(), BotError> {
+    let active: HashSet<String> = platform
+        .active_threads(guild_id)
+        .await?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    for (role_id, name) in platform.roles(guild_id).await? {
+        if parse_solvers_thread_name(&name).is_some() && !active.contains(&name) {
+            platform.delete_role(guild_id, role_id).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::{
+        channel::ChannelType,
+        guild::PremiumTier,
+        id::{ChannelId, GuildId, UserId},
+    };
+
+    use super::{let_in, GateMode};
+    use crate::platform::{fake::FakePlatform, ChatPlatform, Thread};
+
+    const SOLVERS: ChannelId = ChannelId(11);
+
+    async fn thread(platform: &FakePlatform, kind: ChannelType, day: u32) -> Thread {
+        let name = format!("Wordle Solvers {}", day);
+        platform
+            .create_thread(SOLVERS, &name, kind, None)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_gate_mode_parse() {
+        assert_eq!(GateMode::parse("Members"), Some(GateMode::Members));
+        assert_eq!(GateMode::parse("role"), Some(GateMode::Role));
+        assert_eq!(GateMode::parse("open"), Some(GateMode::Open));
+        assert_eq!(GateMode::parse("everyone"), None);
+    }
+
+    #[tokio::test]
+    async fn test_let_in_private() {
+        let platform = FakePlatform::new(PremiumTier::Tier2, &[]);
+        let thread = thread(&platform, ChannelType::PrivateThread, 231).await;
+        let_in(&platform, GuildId(1), &thread, UserId(7))
+            .await
+            .unwrap();
+        assert_eq!(
+            *platform.thread_members.lock().unwrap(),
+            vec![(thread.id, UserId(7))]
+        );
+        assert!(platform.roles.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_let_in_role() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let thread = thread(&platform, ChannelType::PublicThread, 231).await;
+        let_in(&platform, GuildId(1), &thread, UserId(7))
+            .await
+            .unwrap();
+        let_in(&platform, GuildId(1), &thread, UserId(8))
+            .await
+            .unwrap();
+        // Everyone that day shares one role, which can see the solvers channel
+        let roles = platform.roles.lock().unwrap().clone();
+        assert_eq!(roles.len(), 1);
+        let (role_id, name) = &roles[0];
+        assert_eq!(name, "Wordle Solvers 231");
+        assert_eq!(*platform.shown.lock().unwrap(), vec![(SOLVERS, *role_id)]);
+        assert_eq!(
+            *platform.member_roles.lock().unwrap(),
+            vec![(UserId(7), *role_id), (UserId(8), *role_id)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_let_in_next_day() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let yesterday = thread(&platform, ChannelType::PublicThread, 231).await;
+        let today = thread(&platform, ChannelType::PublicThread, 232).await;
+        let_in(&platform, GuildId(1), &yesterday, UserId(7))
+            .await
+            .unwrap();
+        let_in(&platform, GuildId(1), &today, UserId(8))
+            .await
+            .unwrap();
+        // Yesterday's solvers can't see today's thread
+        let roles = platform.roles.lock().unwrap().clone();
+        assert_eq!(roles.len(), 1);
+        let (role_id, name) = &roles[0];
+        assert_eq!(name, "Wordle Solvers 232");
+        assert_eq!(*platform.shown.lock().unwrap(), vec![(SOLVERS, *role_id)]);
+        assert_eq!(
+            *platform.member_roles.lock().unwrap(),
+            vec![(UserId(8), *role_id)]
+        );
+
+        // Someone still on yesterday's is too late to get in
+        let_in(&platform, GuildId(1), &yesterday, UserId(9))
+            .await
+            .unwrap();
+        assert_eq!(platform.roles.lock().unwrap().len(), 1);
+        assert_eq!(platform.member_roles.lock().unwrap().len(), 1);
+
+        // Other games have their own roles
+        let heardle = platform
+            .create_thread(
+                SOLVERS,
+                "Heardle Solvers 16",
+                ChannelType::PublicThread,
+                None,
+            )
+            .await
+            .unwrap();
+        let_in(&platform, GuildId(1), &heardle, UserId(9))
+            .await
+            .unwrap();
+        assert_eq!(platform.roles.lock().unwrap().len(), 2);
+    }
+}
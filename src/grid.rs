@@ -0,0 +1,166 @@
+use crate::detector::Tile;
+
+// How someone has wordle set up, from the squares in their grid
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Theme {
+    // ⬜ instead of ⬛ for absent letters
+    pub light_mode: bool,
+    // The colour blind 🟧 and 🟦 instead of 🟩 and 🟨
+    pub high_contrast: bool,
+}
+
+// Grids with no absent letters can't tell light from dark, those get dark mode.
+pub fn detect_theme(body: &str) -> Theme {
+    Theme {
+        light_mode: body.contains('⬜'),
+        high_contrast: body.contains('🟧') || body.contains('🟦'),
+    }
+}
+
+// Turn a single emoji square into a tile. Handles the light mode (⬜) and
+// high contrast (🟧 for correct, 🟦 for present) variants too.
+fn emoji_to_tile(ch: char) -> Option<Tile> {
+    match ch {
+        '⬛' | '⬜' => Some(Tile::Absent),
+        '🟨' | '🟦' => Some(Tile::Present),
+        '🟩' | '🟧' => Some(Tile::Correct),
+        _ => None,
+    }
+}
+
+// Parse a shared wordle grid into the tiles for each row. Lines without any
+// squares (the "Wordle 229 6/6" header, blank lines) are skipped.
+pub fn parse_grid(body: &str) -> anyhow::Result<Vec<[Tile; 5]>> {
+    let mut rows = vec![];
+    for line in body.lines() {
+        // Emoji sometimes come with a variation selector tacked on
+        let chars: Vec<char> = line.trim().chars().filter(|ch| *ch != '\u{fe0f}').collect();
+        let tiles: Vec<Tile> = chars.iter().filter_map(|ch| emoji_to_tile(*ch)).collect();
+        if tiles.is_empty() {
+            continue;
+        }
+        if tiles.len() != 5 || chars.len() != 5 {
+            return Err(anyhow::anyhow!(
+                "grid rows need to be 5 squares, got {}",
+                line
+            ));
+        }
+        let mut row = [Tile::Absent; 5];
+        row.copy_from_slice(&tiles);
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        return Err(anyhow::anyhow!("no grid found in {}", body));
+    }
+    Ok(rows)
+}
+
+// Draw a row back out with the same squares as the theme.
+pub fn row_to_emoji(row: &[Tile; 5], theme: Theme) -> String {
+    row.iter()
+        .map(|tile| match (tile, theme.light_mode, theme.high_contrast) {
+            (Tile::Absent, false, _) => '⬛',
+            (Tile::Absent, true, _) => '⬜',
+            (Tile::Present, _, false) => '🟨',
+            (Tile::Present, _, true) => '🟦',
+            (Tile::Correct, _, false) => '🟩',
+            (Tile::Correct, _, true) => '🟧',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_theme, parse_grid, row_to_emoji, Theme};
+    use crate::detector::Tile::{Absent as A, Correct as C, Present as P};
+
+    #[test]
+    fn test_parse_grid() {
+        assert_eq!(
+            parse_grid(
+                "⬛🟨🟨⬛⬛
+🟩⬛⬛⬛🟨
+🟩🟩🟩🟩🟩"
+            )
+            .unwrap(),
+            vec![[A, P, P, A, A], [C, A, A, A, P], [C, C, C, C, C]]
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_variants() {
+        // Light mode
+        assert_eq!(
+            parse_grid("⬜🟨⬜⬜🟩\n🟩🟩🟩🟩🟩").unwrap(),
+            vec![[A, P, A, A, C], [C, C, C, C, C]]
+        );
+        // High contrast
+        assert_eq!(
+            parse_grid("⬛🟦⬛⬛🟧\n🟧🟧🟧🟧🟧").unwrap(),
+            vec![[A, P, A, A, C], [C, C, C, C, C]]
+        );
+        // Variation selectors and the share header
+        assert_eq!(
+            parse_grid("Wordle 229 2/6\n\n⬜️🟨⬜️⬜️🟩\n🟩🟩🟩🟩🟩").unwrap(),
+            vec![[A, P, A, A, C], [C, C, C, C, C]]
+        );
+    }
+
+    #[test]
+    fn test_row_to_emoji() {
+        let dark = Theme::default();
+        assert_eq!(row_to_emoji(&[A, P, P, A, C], dark), "⬛🟨🟨⬛🟩");
+        for grid in [
+            "⬛🟨🟨⬛⬛\n🟩🟩🟩🟩🟩",
+            "⬜🟨🟨⬜⬜\n🟩🟩🟩🟩🟩",
+            "⬛🟦🟦⬛⬛\n🟧🟧🟧🟧🟧",
+            "⬜🟦🟦⬜⬜\n🟧🟧🟧🟧🟧",
+        ] {
+            let theme = detect_theme(grid);
+            let rows = parse_grid(grid).unwrap();
+            assert_eq!(
+                rows.iter()
+                    .map(|row| row_to_emoji(row, theme))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                grid
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_theme() {
+        assert_eq!(detect_theme("⬛🟨🟨⬛⬛"), Theme::default());
+        assert_eq!(
+            detect_theme("⬜️🟨⬜️⬜️🟩"),
+            Theme {
+                light_mode: true,
+                high_contrast: false
+            }
+        );
+        assert_eq!(
+            detect_theme("⬛🟦⬛⬛🟧"),
+            Theme {
+                light_mode: false,
+                high_contrast: true
+            }
+        );
+        // Solved in one, nothing to say it's not dark mode
+        assert_eq!(
+            detect_theme("🟧🟧🟧🟧🟧"),
+            Theme {
+                light_mode: false,
+                high_contrast: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_errors() {
+        assert!(parse_grid("").is_err());
+        assert!(parse_grid("train\nweigh").is_err());
+        assert!(parse_grid("⬛🟨🟨⬛").is_err());
+        assert!(parse_grid("⬛🟨🟨⬛⬛⬛").is_err());
+        assert!(parse_grid("⬛🟨x🟨⬛⬛").is_err());
+    }
+}
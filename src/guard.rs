@@ -0,0 +1,200 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::answers::Answer;
+use crate::detector::Tile;
+use crate::grid::parse_grid;
+use crate::words::VALID_WORDS;
+
+// What to do about a message that gives away today's answer
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    // Nothing, the default
+    Off,
+    Delete,
+    // Delete it and post it again in the solvers thread behind spoiler tags
+    Repost,
+    // Leave it, but tell them off
+    Warn,
+}
+
+impl GuardAction {
+    pub fn parse(action: &str) -> Option<GuardAction> {
+        match action.trim().to_lowercase().as_str() {
+            "off" => Some(GuardAction::Off),
+            "delete" => Some(GuardAction::Delete),
+            "repost" => Some(GuardAction::Repost),
+            "warn" => Some(GuardAction::Warn),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Spoiler {
+    // The answer itself, as a word or spelt out in 🇦 letters
+    Answer,
+    // A line of five 🟩 with a word next to it that could be the answer, when
+    // we don't know for sure what it is
+    GreenRow,
+}
+
+// Discord won't take a message with more characters than this
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+// What someone said again behind spoiler tags, cut short if it won't fit
+pub fn repost_text(author: &str, content: &str) -> String {
+    let said = format!("{} said: ||", author);
+    let room = MAX_MESSAGE_LENGTH - said.chars().count() - "||".len();
+    if content.chars().count() <= room {
+        return format!("{}{}||", said, content);
+    }
+    let cut: String = content.chars().take(room - 1).collect();
+    format!("{}{}…||", said, cut)
+}
+
+// 🇦 through 🇿 back to plain letters
+fn regional_to_ascii(c: char) -> char {
+    match c {
+        '🇦'..='🇿' => char::from(b'a' + (c as u32 - '🇦' as u32) as u8),
+        _ => c,
+    }
+}
+
+fn is_solved_row(line: &str) -> bool {
+    match parse_grid(line) {
+        Ok(rows) => rows.len() == 1 && rows[0] == [Tile::Correct; 5],
+        Err(_) => false,
+    }
+}
+
+// Check a message for today's answer. Anything already behind spoiler tags is
+// fine, and so is a solved row next to some other word ("🟩🟩🟩🟩🟩 great").
+// The answer only counts by itself once it's verified, a guess from the word
+// list is as good as any other word.
+pub fn find_spoiler(content: &str, answer: Option<Answer>) -> Option<Spoiler> {
+    lazy_static! {
+        static ref SPOILER_TAGS_REG: Regex = Regex::new(r"(?s)\|\|.*?\|\|").unwrap();
+        static ref WORD_REG: Regex = Regex::new(r"[a-zA-Z]+").unwrap();
+        static ref FIVE_LETTERS_REG: Regex = Regex::new(r"\b[a-zA-Z]{5}\b").unwrap();
+    }
+    let visible = SPOILER_TAGS_REG.replace_all(content, "");
+    let letters: String = visible
+        .chars()
+        .filter(|c| !matches!(c, '\u{200d}' | '\u{fe0f}'))
+        .map(regional_to_ascii)
+        .collect::<String>()
+        .to_lowercase();
+    if let Some(Answer::Verified(answer)) = answer {
+        // Regional letters tend to come with spaces between them
+        let squashed: String = letters.split(' ').collect();
+        if WORD_REG
+            .find_iter(&letters)
+            .chain(WORD_REG.find_iter(&squashed))
+            .any(|word| word.as_str() == answer)
+        {
+            return Some(Spoiler::Answer);
+        }
+        // A solved row with the answer next to it was caught above
+        return None;
+    }
+    for line in visible.lines() {
+        let grid: String = line
+            .chars()
+            .filter(|c| matches!(c, '⬛' | '⬜' | '🟨' | '🟦' | '🟩' | '🟧'))
+            .collect();
+        let answer_next_to_it = FIVE_LETTERS_REG
+            .find_iter(line)
+            .any(|word| VALID_WORDS.contains(&word.as_str().to_lowercase().as_str()));
+        if is_solved_row(&grid) && answer_next_to_it {
+            return Some(Spoiler::GreenRow);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_spoiler, repost_text, GuardAction, Spoiler};
+    use crate::answers::Answer;
+
+    #[test]
+    fn test_guard_action_parse() {
+        assert_eq!(GuardAction::parse("Delete"), Some(GuardAction::Delete));
+        assert_eq!(GuardAction::parse("repost"), Some(GuardAction::Repost));
+        assert_eq!(GuardAction::parse("warn"), Some(GuardAction::Warn));
+        assert_eq!(GuardAction::parse("off"), Some(GuardAction::Off));
+        assert_eq!(GuardAction::parse("ban"), None);
+    }
+
+    #[test]
+    fn test_repost_text() {
+        assert_eq!(repost_text("<@1>", "abide"), "<@1> said: ||abide||");
+        let long = repost_text("<@1>", &"🟩".repeat(3000));
+        assert_eq!(long.chars().count(), 2000);
+        assert!(long.starts_with("<@1> said: ||🟩"));
+        assert!(long.ends_with("🟩…||"));
+    }
+
+    #[test]
+    fn test_find_spoiler() {
+        let answer = Some(Answer::Verified("abide"));
+        assert_eq!(
+            find_spoiler("it was ABIDE lol", answer),
+            Some(Spoiler::Answer)
+        );
+        assert_eq!(find_spoiler("abide.", answer), Some(Spoiler::Answer));
+        assert_eq!(find_spoiler("🇦 🇧 🇮 🇩 🇪", answer), Some(Spoiler::Answer));
+        assert_eq!(find_spoiler("🇦🇧🇮🇩🇪", answer), Some(Spoiler::Answer));
+        assert_eq!(
+            find_spoiler("🟩🟩🟩🟩🟩 abide", answer),
+            Some(Spoiler::Answer)
+        );
+        // Without knowing the answer, any word that could be one counts
+        assert_eq!(
+            find_spoiler("shake 🟧🟧🟧🟧🟧", None),
+            Some(Spoiler::GreenRow)
+        );
+        assert_eq!(
+            find_spoiler("🟩🟩🟩🟩🟩 Great", None),
+            Some(Spoiler::GreenRow)
+        );
+
+        // Spoiler tagged, other words and plain shares are all fine
+        assert_eq!(find_spoiler("it was ||abide|| lol", answer), None);
+        assert_eq!(find_spoiler("||🟩🟩🟩🟩🟩 abide||", answer), None);
+        assert_eq!(find_spoiler("abided by the rules", answer), None);
+        assert_eq!(
+            find_spoiler("Wordle 229 2/6\n⬛🟨🟨⬛⬛\n🟩🟩🟩🟩🟩", answer),
+            None
+        );
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 wow", answer), None);
+        assert_eq!(find_spoiler("abide", None), None);
+        // Celebrating isn't spoiling
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 first try", answer), None);
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 great", answer), None);
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 shake", answer), None);
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 yesss", None), None);
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 lolol", None), None);
+    }
+
+    #[test]
+    fn test_find_spoiler_guess() {
+        // The word list's answer for the day isn't to be trusted, so it's just
+        // chat unless there's a solved row next to it
+        let guess = Some(Answer::Guess("aloft"));
+        assert_eq!(find_spoiler("the kite stayed aloft all day", guess), None);
+        assert_eq!(find_spoiler("ALOFT", guess), None);
+        assert_eq!(
+            find_spoiler("🟩🟩🟩🟩🟩 aloft", guess),
+            Some(Spoiler::GreenRow)
+        );
+        assert_eq!(
+            find_spoiler("🟩🟩🟩🟩🟩 shake", guess),
+            Some(Spoiler::GreenRow)
+        );
+        assert_eq!(find_spoiler("🟩🟩🟩🟩🟩 lolol", guess), None);
+    }
+}
@@ -1,36 +1,112 @@
-use std::env;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
 
+mod answers;
+mod cache;
+mod commands;
+mod config;
 mod detector;
+mod error;
+mod games;
+mod gate;
+mod grid;
+mod guard;
+mod platform;
+mod recap;
+mod settings;
+mod slash;
+mod stats;
+mod store;
+mod threads;
 mod words;
-use detector::{calculate_word_possibilities, parse_words_list};
+use answers::day_for_date;
+use cache::ThreadCache;
+use chrono::Utc;
+use commands::Reply;
+use config::{Config, SpoilerPolicy};
+use error::{needs, BotError};
+use games::{parse_share, welcome_message, Game, Share};
+use gate::{let_in, GateMode};
+use guard::{find_spoiler, repost_text, GuardAction};
 use lazy_static::lazy_static;
+use platform::{ChatPlatform, Thread};
+use recap::{run_recaps, Clock, Recapper, SystemClock};
 use regex::Regex;
 use serenity::{
     async_trait,
     model::{
-        channel::{ChannelType, Message},
+        channel::{Channel, ChannelType, GuildChannel, Message, PartialGuildChannel},
         gateway::Ready,
-        guild::PremiumTier,
-        misc::Mention,
+        guild::Guild,
+        interactions::Interaction,
     },
     prelude::*,
 };
+use settings::{guild_settings, GuildSettings};
+use stats::Window;
+use store::{open_store, GameResult, Store};
+use threads::{
+    explain_placement, find_solvers_thread, latest_solvers_thread, lock_old_threads,
+    solvers_thread_name, update_summary, welcome_solver, ThreadStrategy,
+};
 
 #[tokio::main]
 async fn main() {
-    let token = env::var("WORDLE_TOKEN").expect("Expected a token in the environment");
+    let config = Config::from_env().expect("Err loading config");
+    let store: Arc<dyn Store> = Arc::from(open_store(&config.store).expect("Err opening store"));
 
-    let mut client = Client::builder(&token)
-        .event_handler(Handler)
+    let mut builder = Client::builder(&config.token);
+    if let Some(application_id) = config.application_id {
+        builder = builder.application_id(application_id);
+    }
+    let recaps = config.recaps.clone();
+    let mut client = builder
+        .event_handler(Handler {
+            store: store.clone(),
+            config,
+            threads: ThreadCache::default(),
+            commands_checked: AtomicBool::new(false),
+            newest_wordle: AtomicU32::new(0),
+        })
         .await
         .expect("Err creating client");
 
+    if !recaps.is_empty() {
+        let clock = SystemClock;
+        let recapper = Recapper::new(store, recaps, clock.now());
+        tokio::spawn(run_recaps(
+            client.cache_and_http.http.clone(),
+            recapper,
+            clock,
+        ));
+    }
+
     if let Err(why) = client.start().await {
         println!("Client error: {:?}", why);
     }
 }
 
-struct Handler;
+struct Handler {
+    store: Arc<dyn Store>,
+    config: Config,
+    threads: ThreadCache,
+    // Whether the slash commands have been checked against discord's
+    commands_checked: AtomicBool,
+    // The newest wordle anyone's posted, for the spoiler guard
+    newest_wordle: AtomicU32,
+}
+
+// The ! commands people can type in a channel
+#[derive(Debug, PartialEq)]
+enum TextCommand<'a> {
+    WordleStats(&'a str),
+    WordlePath(Option<&'a str>, &'a str),
+    Stats(Option<&'a str>),
+    Leaderboard(Option<&'a str>, Window),
+    Config(&'a str),
+    Where(&'a str),
+}
 
 #[async_trait]
 impl EventHandler for Handler {
@@ -38,102 +114,600 @@ impl EventHandler for Handler {
         if msg.author.bot {
             return;
         }
+        let settings = match msg.guild_id {
+            Some(guild_id) => guild_settings(self.store.as_ref(), &self.config, guild_id.0),
+            None => GuildSettings::default(),
+        };
         let content = msg.content.trim();
-        if let Some(query) = extract_wordle_stats_query(content) {
-            let mut rows = match parse_words_list(query) {
-                Ok(rows) => rows,
-                Err(e) => {
-                    dbg!(e);
-                    msg.reply(
-                        ctx,
-                        "Weird, couldn't parse your message, I need 5 letters per row.",
-                    )
-                    .await
-                    .unwrap();
-                    return;
-                }
+        let prefixed = with_prefix(content, settings.prefix());
+        let (during, handled) =
+            if let Some(command) = prefixed.as_deref().and_then(extract_text_command) {
+                let handled = self.run_text_command(&ctx, &msg, &settings, command).await;
+                ("command", handled)
+            } else if let Some((game, share)) =
+                parse_share(content).filter(|(game, _)| settings.game_enabled(game.name()))
+            {
+                let handled = self
+                    .handle_share(ctx.http.as_ref(), &msg, &settings, game, &share)
+                    .await;
+                ("share", handled)
+            } else {
+                ("guard", self.guard_spoilers(&ctx, &msg, &settings).await)
             };
-            let res = match calculate_word_possibilities(&mut rows) {
-                Ok(res) => res,
-                Err(e) => {
-                    dbg!(e);
-                    msg.reply(ctx, "Weird, something went wrong running, not sure what")
-                        .await
-                        .unwrap();
-                    return;
+        if let Err(e) = handled {
+            self.report(ctx.http.as_ref(), &msg, during, e).await;
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::ApplicationCommand(command) => {
+                let handled = slash::handle_command(
+                    &ctx.http,
+                    self.store.as_ref(),
+                    &self.config,
+                    &self.threads,
+                    &command,
+                )
+                .await;
+                if let Err(e) = handled {
+                    slash::report(&ctx.http, &command, "slash", e).await;
                 }
-            };
-            let mut result = String::new();
-            for (i, row) in rows.iter().enumerate() {
-                let row_str = format!(
-                    "`{}` - {}({}): (`{}`)\n",
-                    row.iter().collect::<String>(),
-                    res[i].2,
-                    res[i].1,
-                    res[i].0.join("`, `"),
-                );
-                result.push_str(&row_str);
             }
-            msg.reply(ctx, result).await.unwrap();
+            Interaction::Autocomplete(autocomplete) => {
+                let handled =
+                    slash::handle_autocomplete(&ctx.http, self.store.as_ref(), &autocomplete).await;
+                if let Err(e) = handled {
+                    e.log("autocomplete");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        println!("{} is connected!", ready.user.name);
+        // Ready comes again every time the gateway reconnects, but the
+        // commands only need checking once
+        if self.config.application_id.is_none()
+            || self.commands_checked.swap(true, Ordering::SeqCst)
+        {
             return;
         }
-        if let Some((name, day, result, body)) = extract_wordlelike_data(content) {
-            let thread_name = format!("{} Solvers {}", name, day);
-            let chan = msg.channel_id.to_channel(&ctx.http).await.unwrap();
-            let guild_chan = chan.guild().unwrap();
-            let threads = guild_chan
-                .guild_id
-                .get_active_threads(&ctx.http)
-                .await
-                .unwrap();
-            let thread = match threads.threads.iter().find(|t| t.name == thread_name) {
-                Some(t) => t.clone(),
-                None => {
-                    let guild = msg.guild_id.unwrap().to_partial_guild(&ctx).await.unwrap();
-                    let thread_type = match guild.premium_tier {
-                        PremiumTier::Tier3 | PremiumTier::Tier2 => ChannelType::PrivateThread,
-                        _ => ChannelType::PublicThread,
-                    };
-                    let chan_id = match thread_type {
-                        ChannelType::PublicThread => {
-                            match guild
-                                .channels(&ctx)
-                                .await
-                                .unwrap()
-                                .values()
-                                .find(|c| c.name == format!("{}_solvers", guild_chan.name))
-                            {
-                                Some(chan) => chan.id,
-                                None => msg.channel_id,
-                            }
-                        }
-                        ChannelType::PrivateThread => msg.channel_id,
-                        _ => unreachable!(),
-                    };
-                    chan_id
-                        .create_private_thread(&ctx, |f| {
-                            f.name(thread_name.clone());
-                            f.kind(thread_type);
-                            f.rate_limit_per_user(0);
-                            f
-                        })
-                        .await
-                        .unwrap()
+        match slash::register_commands(&ctx.http).await {
+            Ok(true) => println!("Registered slash commands"),
+            Ok(false) => {}
+            Err(e) => {
+                e.log("register");
+                // Worth another go next time
+                self.commands_checked.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+
+    // Each guild comes with its active threads when we connect
+    async fn guild_create(&self, _ctx: Context, guild: Guild) {
+        let threads = guild.threads.into_iter().map(Thread::from).collect();
+        self.threads.reconcile(guild.id, threads);
+    }
+
+    async fn thread_create(&self, _ctx: Context, thread: GuildChannel) {
+        self.threads.insert(Thread::from(thread));
+    }
+
+    // Including when it's archived, so the next solver unarchives it
+    async fn thread_update(&self, _ctx: Context, thread: GuildChannel) {
+        self.threads.insert(Thread::from(thread));
+    }
+
+    async fn thread_delete(&self, _ctx: Context, thread: PartialGuildChannel) {
+        self.threads.remove(thread.id);
+    }
+}
+
+impl Handler {
+    // Log what went wrong, and tell the channel if there's something they can
+    // do about it
+    async fn report(&self, platform: &dyn ChatPlatform, msg: &Message, during: &str, e: BotError) {
+        e.log(during);
+        if let Some(text) = e.user_message() {
+            if let Err(e) = platform.reply(msg.channel_id, msg.id, &text).await {
+                e.log("report");
+            }
+        }
+    }
+
+    // Record the result and welcome them to the solvers thread, making the
+    // thread if they're the first.
+    async fn handle_share(
+        &self,
+        platform: &dyn ChatPlatform,
+        msg: &Message,
+        settings: &GuildSettings,
+        game: &dyn Game,
+        share: &Share<'_>,
+    ) -> Result<(), BotError> {
+        let guild_id = msg.guild_id.ok_or(BotError::NoGuild)?;
+        if game.name() == commands::DEFAULT_GAME {
+            self.newest_wordle.fetch_max(share.day, Ordering::SeqCst);
+        }
+        let record = GameResult {
+            guild_id: guild_id.0,
+            channel_id: msg.channel_id.0,
+            user_id: msg.author.id.0,
+            game: game.name().to_string(),
+            day: share.day,
+            score: share.score(),
+            hard_mode: share.hard_mode,
+            grid: share.body.to_string(),
+            posted_at: msg.timestamp.timestamp(),
+        };
+        // Still worth making the thread if this fails
+        if let Err(e) = self.store.record_result(&record) {
+            BotError::Store(e).log("record");
+        }
+        let thread_name = solvers_thread_name(game.name(), share.day);
+        let channel_name = platform.channel_name(msg.channel_id).await?;
+        let placement = settings.placement(guild_id, msg.channel_id, &channel_name);
+        let (thread, created) = match welcome_solver(
+            platform,
+            &self.threads,
+            &placement,
+            &thread_name,
+            &welcome_message(game, share, msg.author.mention()),
+        )
+        .await?
+        {
+            Some(welcomed) => welcomed,
+            // No threads in this channel
+            None => return Ok(()),
+        };
+        if created {
+            if let Err(e) = lock_old_threads(
+                platform,
+                &self.threads,
+                guild_id,
+                game.name(),
+                share.day,
+                Utc::now(),
+            )
+            .await
+            {
+                e.log("lock");
+            }
+        }
+        if placement.gate != GateMode::Open {
+            if let Err(e) = let_in(platform, guild_id, &thread, msg.author.id).await {
+                e.log("gate");
+            }
+        }
+        if let Err(e) =
+            update_summary(platform, self.store.as_ref(), &thread, game, share.day).await
+        {
+            e.log("summary");
+        }
+        Ok(())
+    }
+
+    async fn run_text_command(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        command: TextCommand<'_>,
+    ) -> Result<(), BotError> {
+        // There's no slash command for these
+        match command {
+            TextCommand::Config(args) => {
+                return self.configure(ctx.http.as_ref(), msg, settings, args).await
+            }
+            TextCommand::Where(args) => return self.explain_where(ctx, msg, settings, args).await,
+            _ => {}
+        }
+        if settings.slash_only() {
+            msg.reply(
+                ctx,
+                "We've moved to slash commands here, try typing / instead!",
+            )
+            .await?;
+            return Ok(());
+        }
+        let reply = match command {
+            TextCommand::WordleStats(query) => {
+                return self.send_wordle_stats(ctx, msg, settings, query).await
+            }
+            TextCommand::WordlePath(answer, grid) => Reply::Text(commands::wordle_path(
+                &self.config.answers,
+                answer,
+                grid,
+                Utc::now().date_naive(),
+            )),
+            TextCommand::Stats(game) => {
+                let results = self.lookup_guild_results(msg)?;
+                let user = msg.mentions.first().unwrap_or(&msg.author);
+                Reply::Text(commands::stats(&results, user.id.0, game))
+            }
+            TextCommand::Leaderboard(game, window) => {
+                let results = self.lookup_guild_results(msg)?;
+                commands::leaderboard(&results, game, window)
+            }
+            TextCommand::Config(_) | TextCommand::Where(_) => unreachable!("handled above"),
+        };
+        match reply {
+            Reply::Text(text) => {
+                msg.reply(ctx, text).await?;
+            }
+            Reply::Embed {
+                title,
+                description,
+                footer,
+            } => {
+                msg.channel_id
+                    .send_message(ctx, |m| {
+                        m.reference_message(msg);
+                        m.embed(|e| {
+                            e.title(title);
+                            e.description(description);
+                            e.footer(|f| f.text(footer));
+                            e
+                        });
+                        m
+                    })
+                    .await
+                    .map_err(needs("Embed Links"))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Wordle stats list this puzzle's words, so they only go out publicly if
+    // the guild wants them in spoiler tags. Otherwise the guesses get taken
+    // out of the channel too.
+    async fn send_wordle_stats(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        query: &str,
+    ) -> Result<(), BotError> {
+        let guild_id = match msg.guild_id {
+            Some(guild_id) => guild_id,
+            // Already a DM
+            None => {
+                msg.reply(
+                    ctx,
+                    commands::run_wordle_stats(
+                        query,
+                        false,
+                        &self.config.answers,
+                        self.config.words_to_show,
+                    )
+                    .await,
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+        let policy = settings.spoiler_policy();
+        if policy == SpoilerPolicy::Spoiler {
+            msg.reply(
+                ctx,
+                commands::run_wordle_stats(
+                    query,
+                    true,
+                    &self.config.answers,
+                    self.config.words_to_show,
+                )
+                .await,
+            )
+            .await?;
+            return Ok(());
+        }
+        if let Err(e) = msg.delete(ctx).await {
+            needs("Manage Messages")(e).log("wordlestats");
+        }
+        let stats = commands::run_wordle_stats(
+            query,
+            false,
+            &self.config.answers,
+            self.config.words_to_show,
+        )
+        .await;
+        if policy == SpoilerPolicy::Thread {
+            if let Some(thread) = latest_solvers_thread(
+                ctx.http.as_ref(),
+                &self.threads,
+                self.store.as_ref(),
+                guild_id,
+                msg.author.id.0,
+                commands::DEFAULT_GAME,
+            )
+            .await
+            {
+                let text = format!("{}\n{}", msg.author.mention(), stats);
+                match ctx.http.post_message(thread.id, &text).await {
+                    Ok(()) => return Ok(()),
+                    // Falls back to a DM
+                    Err(e) => e.log("stats thread"),
                 }
-            };
-            thread
+            }
+        }
+        if msg
+            .author
+            .direct_message(ctx, |m| m.content(stats))
+            .await
+            .is_err()
+        {
+            msg.channel_id
                 .say(
-                    &ctx,
-                    get_welcome_message(name, msg.author.mention(), result, body),
+                    ctx,
+                    format!(
+                        "{} I couldn't DM you your stats, are your DMs open?",
+                        msg.author.mention()
+                    ),
                 )
-                .await
-                .unwrap();
+                .await?;
         }
+        Ok(())
     }
 
-    async fn ready(&self, _: Context, ready: Ready) {
-        println!("{} is connected!", ready.user.name);
+    // Deal with messages that give away today's wordle outside of the solvers
+    // threads, if the guild wants that.
+    async fn guard_spoilers(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+    ) -> Result<(), BotError> {
+        let guild_id = match msg.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(()),
+        };
+        let action = settings.spoiler_guard();
+        // Solvers threads are where you're meant to talk about it
+        if action == GuardAction::Off || self.threads.is_thread(msg.channel_id) {
+            return Ok(());
+        }
+        // Today's wordle by the date, and whatever the newest one anyone's
+        // posted is since people a timezone ahead are already on the next
+        let newest = match self.newest_wordle.load(Ordering::SeqCst) {
+            0 => None,
+            day => Some(day),
+        };
+        let mut days: Vec<u32> = day_for_date(Utc::now().date_naive())
+            .into_iter()
+            .chain(newest)
+            .collect();
+        days.dedup();
+        let day = match days.into_iter().find(|day| {
+            let answer = self.config.answers.answer_for_day(*day);
+            find_spoiler(&msg.content, answer).is_some()
+        }) {
+            Some(day) => day,
+            None => return Ok(()),
+        };
+        // Other threads are fine too
+        let channel = match msg.channel_id.to_channel(ctx).await {
+            Ok(Channel::Guild(channel))
+                if !matches!(
+                    channel.kind,
+                    ChannelType::PublicThread | ChannelType::PrivateThread
+                ) =>
+            {
+                channel
+            }
+            _ => return Ok(()),
+        };
+
+        if action == GuardAction::Warn {
+            msg.reply(
+                ctx,
+                "Careful, that gives away today's wordle! Wrap it in ||spoiler tags|| next time.",
+            )
+            .await?;
+            return Ok(());
+        }
+        let thread = match action {
+            GuardAction::Repost => {
+                let placement = settings.placement(guild_id, msg.channel_id, &channel.name);
+                let name = solvers_thread_name(commands::DEFAULT_GAME, day);
+                match find_solvers_thread(ctx.http.as_ref(), &self.threads, &placement, &name).await
+                {
+                    Ok(thread) => thread,
+                    Err(e) => {
+                        e.log("repost");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        // It still comes down if the repost doesn't go up, it just isn't moved
+        let repost = repost_text(&msg.author.mention().to_string(), &msg.content);
+        let thread = match thread {
+            Some(thread) => match ctx.http.post_message(thread.id, &repost).await {
+                Ok(()) => Some(thread),
+                Err(e) => {
+                    e.log("repost");
+                    None
+                }
+            },
+            None => None,
+        };
+        msg.delete(ctx).await.map_err(needs("Manage Messages"))?;
+        let notice = match thread {
+            Some(thread) => format!(
+                "{} that gives away today's wordle, I moved it to {}",
+                msg.author.mention(),
+                thread.id.mention()
+            ),
+            None => format!(
+                "{} that gives away today's wordle, so I took it down",
+                msg.author.mention()
+            ),
+        };
+        msg.channel_id.say(ctx, notice).await?;
+        Ok(())
+    }
+
+    // `!wordlebot config` shows the guild's settings to anyone, changing them
+    // needs Manage Server
+    async fn configure(
+        &self,
+        platform: &dyn ChatPlatform,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> Result<(), BotError> {
+        msg.guild_id.ok_or(BotError::NoGuild)?;
+        if args.is_empty() {
+            let described = format!(
+                "Settings here, change them with `!wordlebot config <setting> <value>` (or `reset`):\n{}",
+                settings.describe()
+            );
+            return platform.reply(msg.channel_id, msg.id, &described).await;
+        }
+        if let Some(changed) = self
+            .change_settings(platform, msg, |s| s.apply(args))
+            .await?
+        {
+            platform.reply(msg.channel_id, msg.id, &changed).await?;
+        }
+        Ok(())
+    }
+
+    // `!wordlebot where` says where threads for results posted in the channel
+    // go and what permissions that needs, `!wordlebot where <strategy>` (or
+    // `reset`) changes it
+    async fn explain_where(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> Result<(), BotError> {
+        let guild_id = msg.guild_id.ok_or(BotError::NoGuild)?;
+        let channel = msg
+            .channel_id
+            .to_channel(ctx)
+            .await?
+            .guild()
+            .ok_or(BotError::NoGuild)?;
+        let mut settings = settings.clone();
+        if !args.is_empty() {
+            let changed = self
+                .change_settings(ctx.http.as_ref(), msg, |stored| {
+                    if args.eq_ignore_ascii_case("reset") {
+                        stored.channels.remove(&channel.id.to_string());
+                    } else {
+                        let strategy = ThreadStrategy::try_from(args.to_string())?;
+                        stored.channels.insert(channel.id.to_string(), strategy);
+                    }
+                    Ok(String::new())
+                })
+                .await?;
+            if changed.is_none() {
+                return Ok(());
+            }
+            settings = guild_settings(self.store.as_ref(), &self.config, guild_id.0);
+        }
+        let placement = settings.placement(guild_id, channel.id, &channel.name);
+        let explanation = explain_placement(ctx.http.as_ref(), &placement).await?;
+        msg.reply(ctx, explanation).await?;
+        Ok(())
     }
+
+    // Change the guild's stored settings, if whoever sent the message can
+    // Manage Server. Gives back what `change` says changed, or nothing if they
+    // aren't allowed.
+    async fn change_settings(
+        &self,
+        platform: &dyn ChatPlatform,
+        msg: &Message,
+        change: impl FnOnce(&mut GuildSettings) -> Result<String, String>,
+    ) -> Result<Option<String>, BotError> {
+        let guild_id = msg.guild_id.ok_or(BotError::NoGuild)?;
+        let roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles[..]);
+        let permissions = platform
+            .member_permissions(guild_id, msg.author.id, roles)
+            .await?;
+        if !permissions.manage_guild() {
+            platform
+                .reply(
+                    msg.channel_id,
+                    msg.id,
+                    "Only people who can Manage Server can change my settings.",
+                )
+                .await?;
+            return Ok(None);
+        }
+        let mut stored = self
+            .store
+            .guild_settings(guild_id.0)
+            .map_err(BotError::Store)?;
+        let changed = change(&mut stored).map_err(BotError::Parse)?;
+        self.store
+            .save_guild_settings(guild_id.0, &stored)
+            .map_err(BotError::Store)?;
+        Ok(Some(changed))
+    }
+
+    // All the results recorded in the message's guild
+    fn lookup_guild_results(&self, msg: &Message) -> Result<Vec<GameResult>, BotError> {
+        let guild_id = msg.guild_id.ok_or(BotError::NoGuild)?;
+        self.store
+            .guild_results(guild_id.0)
+            .map_err(BotError::Store)
+    }
+}
+
+// Text commands typed with the guild's prefix, rewritten to start with ! so
+// they all get matched the same way. `!wordlebot` works whatever the prefix is
+// so nobody can lose track of it.
+fn with_prefix<'a>(content: &'a str, prefix: &str) -> Option<Cow<'a, str>> {
+    if prefix == "!" || content.starts_with("!wordlebot") {
+        return Some(Cow::Borrowed(content));
+    }
+    let rest = content.strip_prefix(prefix)?;
+    Some(Cow::Owned(format!("!{}", rest)))
+}
+
+fn extract_text_command(content: &str) -> Option<TextCommand<'_>> {
+    if let Some((command, args)) = extract_wordlebot_command(content) {
+        return match command {
+            "config" => Some(TextCommand::Config(args)),
+            _ => Some(TextCommand::Where(args)),
+        };
+    }
+    if let Some(query) = extract_wordle_stats_query(content) {
+        return Some(TextCommand::WordleStats(query));
+    }
+    if let Some((answer, grid)) = extract_wordle_path_query(content) {
+        return Some(TextCommand::WordlePath(answer, grid));
+    }
+    if let Some(game) = extract_stats_query(content) {
+        return Some(TextCommand::Stats(game));
+    }
+    if let Some((game, window)) = extract_leaderboard_query(content) {
+        return Some(TextCommand::Leaderboard(game, window));
+    }
+    None
+}
+
+// `!wordlebot config [<setting> <value>]` or `!wordlebot where [strategy]`,
+// giving back which and the rest
+fn extract_wordlebot_command(content: &str) -> Option<(&str, &str)> {
+    lazy_static! {
+        static ref WORDLEBOT_REG: Regex =
+            Regex::new(r"^!wordlebot\s+(config|where)((?s).*)$").unwrap();
+    }
+    let captures = WORDLEBOT_REG.captures(content)?;
+    let args = captures.get(2)?.as_str();
+    // Has to be its own word
+    if !args.is_empty() && !args.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((captures.get(1)?.as_str(), args.trim()))
 }
 
 fn extract_wordle_stats_query(content: &str) -> Option<&str> {
@@ -145,226 +719,312 @@ fn extract_wordle_stats_query(content: &str) -> Option<&str> {
     Some(result)
 }
 
-fn extract_wordlelike_data(content: &str) -> Option<(&str, u32, &str, &str)> {
+// `!stats [@user] [game]`, giving back the game if there is one. The user
+// comes from the message's mentions.
+fn extract_stats_query(content: &str) -> Option<Option<&str>> {
     lazy_static! {
-        static ref WORDLELIKE_REG: Regex = Regex::new(r"^#?(?:Daily |I solved today's )?([a-zA-Z]*) \(?#?(\d+)\)? ?([\dX])?(?:/6)?\*?((.|\n)*)?$").unwrap();
-    }
-    let captures = WORDLELIKE_REG.captures(content)?;
-    let name = captures.get(1)?.as_str();
-    let day = captures.get(2)?.as_str().parse::<u32>().ok()?;
-    let result = match captures.get(3) {
-        Some(m) => m.as_str(),
-        None => "",
-    };
-    let body = match captures.get(4) {
-        Some(body) => body.as_str().trim(),
-        None => "",
-    };
-    Some((name, day, result, body))
+        static ref STATS_REG: Regex = Regex::new(r"^!stats(\s.*)?$").unwrap();
+    }
+    let captures = STATS_REG.captures(content)?;
+    let args = captures.get(1).map_or("", |m| m.as_str());
+    Some(args.split_whitespace().find(|arg| !arg.starts_with("<@")))
 }
 
-fn get_welcome_message(typ: &str, author: Mention, result: &str, body: &str) -> String {
-    let (suffix_msg, result) = match typ {
-        "Wordle" | "Tradle" => (
-            match result {
-                "1" => "WTFFF?!?!?!",
-                "2" => "Master! You're a master!",
-                "5" => "Just made it!",
-                "6" => "Phew! That was a close one!",
-                "X" => "Nutz! Better luck next time!",
-                _ => "Nice! You got it!",
-            },
-            format!("{}/6", result),
-        ),
-        _ => ("Nice!", result.to_string()),
-    };
-    format!(
-        "Welcome to the secret {} club {}\n{}\n{} {}",
-        typ, author, body, result, suffix_msg
-    )
+// `!leaderboard [game] [week|month|all]`, in either order
+fn extract_leaderboard_query(content: &str) -> Option<(Option<&str>, Window)> {
+    lazy_static! {
+        static ref LEADERBOARD_REG: Regex = Regex::new(r"^!leaderboard(\s.*)?$").unwrap();
+    }
+    let captures = LEADERBOARD_REG.captures(content)?;
+    let args = captures.get(1).map_or("", |m| m.as_str());
+    let mut game = None;
+    let mut window = Window::All;
+    for arg in args.split_whitespace() {
+        match Window::parse(arg) {
+            Some(w) => window = w,
+            None => game = game.or(Some(arg)),
+        }
+    }
+    Some((game, window))
+}
+
+// `!wordlepath [answer or wordle number]` and then the grid. The answer has to
+// be on the same line, otherwise it's worked out from the grid.
+fn extract_wordle_path_query(content: &str) -> Option<(Option<&str>, &str)> {
+    lazy_static! {
+        static ref WORDLE_PATH_REG: Regex =
+            Regex::new(r"!wordlepath(?:[ \t]+(\S+))?((?s).*)").unwrap();
+    }
+    let captures = WORDLE_PATH_REG.captures(content)?;
+    let answer = captures.get(1).map(|answer| answer.as_str());
+    let grid = captures.get(2)?.as_str().trim();
+    Some((answer, grid))
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::platform::fake::FakePlatform;
     use crate::*;
+    use serenity::model::{guild::PremiumTier, id::ChannelId};
 
     #[test]
-    fn test_wordle_regex() {
-        assert_eq!(extract_wordlelike_data("Wordle 1 1/6").unwrap(), ("Wordle", 1, "1", ""));
-        assert_eq!(
-            extract_wordlelike_data("Wordle 200 3/6*").unwrap(),
-            ("Wordle", 200, "3", "")
-        );
-        assert_eq!(extract_wordlelike_data("Wordle 9 X/6").unwrap(), ("Wordle", 9, "X", ""));
+    fn test_wordle_stats() {
         assert_eq!(
-            extract_wordlelike_data(
-                "Wordle 229 6/6
-⬛🟨🟨⬛⬛
-🟩⬛⬛⬛🟨
-🟩🟩⬛⬛⬛
-🟩🟩⬛⬛⬛
-🟩🟩⬛⬛🟨
-🟩🟩🟩🟩🟩
-"
+            extract_wordle_stats_query(
+                "!wordlestats
+train
+weigh
+slide
+oxide"
             )
             .unwrap(),
-            (
-                "Wordle",
-                229,
-                "6",
-                "⬛🟨🟨⬛⬛
-🟩⬛⬛⬛🟨
-🟩🟩⬛⬛⬛
-🟩🟩⬛⬛⬛
-🟩🟩⬛⬛🟨
-🟩🟩🟩🟩🟩"
-            )
+            "train
+weigh
+slide
+oxide"
         );
     }
 
     #[test]
-    fn test_heardle_regex() {
-        assert_eq!(extract_wordlelike_data("#Heardle #16").unwrap(), ("Heardle", 16, "", ""));
-        assert_eq!(extract_wordlelike_data("Heardle 16").unwrap(), ("Heardle", 16, "", ""));
+    fn test_stats_query() {
+        assert_eq!(extract_stats_query("!stats"), Some(None));
+        assert_eq!(extract_stats_query("!stats Heardle"), Some(Some("Heardle")));
         assert_eq!(
-            extract_wordlelike_data(
-                "#Heardle #16
+            extract_stats_query("!stats <@!1234> Quordle"),
+            Some(Some("Quordle"))
+        );
+        assert_eq!(extract_stats_query("!stats <@1234>"), Some(None));
+        assert_eq!(extract_stats_query("!statsy"), None);
+        assert_eq!(extract_stats_query("Wordle 229 6/6"), None);
+    }
 
-🔈🟥⬛️⬛️🟩⬜️⬜️"
-            )
-            .unwrap(),
-            ("Heardle", 16, "", "🔈🟥⬛️⬛️🟩⬜️⬜️")
+    #[test]
+    fn test_leaderboard_query() {
+        assert_eq!(
+            extract_leaderboard_query("!leaderboard"),
+            Some((None, Window::All))
+        );
+        assert_eq!(
+            extract_leaderboard_query("!leaderboard Heardle week"),
+            Some((Some("Heardle"), Window::Week))
         );
+        assert_eq!(
+            extract_leaderboard_query("!leaderboard month"),
+            Some((None, Window::Month))
+        );
+        assert_eq!(extract_leaderboard_query("!leaderboards"), None);
     }
 
     #[test]
-    fn test_tradle_regex() {
-        assert_eq!(extract_wordlelike_data("#Tradle #7 1/6").unwrap(), ("Tradle", 7, "1", ""));
-        assert_eq!(extract_wordlelike_data("Tradle 7 1/6").unwrap(), ("Tradle", 7, "1", ""));
+    fn test_text_command() {
         assert_eq!(
-            extract_wordlelike_data(
-                "#Tradle #7 1/6
-🟩🟩🟩🟩🟩
-https://oec.world/en/tradle"
-            )
-            .unwrap(),
-            (
-                "Tradle",
-                7,
-                "1",
-                "🟩🟩🟩🟩🟩
-https://oec.world/en/tradle"
-            )
+            extract_text_command("!wordlestats\ntrain\noxide"),
+            Some(TextCommand::WordleStats("train\noxide"))
+        );
+        assert_eq!(
+            extract_text_command("!wordlepath abide\n🟩🟩🟩🟩🟩"),
+            Some(TextCommand::WordlePath(Some("abide"), "🟩🟩🟩🟩🟩"))
         );
+        assert_eq!(
+            extract_text_command("!stats"),
+            Some(TextCommand::Stats(None))
+        );
+        assert_eq!(
+            extract_text_command("!leaderboard week"),
+            Some(TextCommand::Leaderboard(None, Window::Week))
+        );
+        assert_eq!(
+            extract_text_command("!wordlebot config prefix ?"),
+            Some(TextCommand::Config("prefix ?"))
+        );
+        assert_eq!(
+            extract_text_command("!wordlebot where"),
+            Some(TextCommand::Where(""))
+        );
+        assert_eq!(extract_text_command("Wordle 229 6/6"), None);
     }
 
     #[test]
-    fn test_quordle_regex() {
+    fn test_wordlebot_command() {
         assert_eq!(
-            extract_wordlelike_data(
-                "Daily Quordle #50
-5️⃣4️⃣
-6️⃣7️⃣"
-            )
-            .unwrap(),
-            (
-                "Quordle",
-                50,
-                "",
-                "5️⃣4️⃣
-6️⃣7️⃣"
-            )
+            extract_wordlebot_command("!wordlebot config"),
+            Some(("config", ""))
         );
         assert_eq!(
-            extract_wordlelike_data(
-                "Daily Quordle 50
-5️⃣4️⃣
-6️⃣7️⃣"
-            )
-            .unwrap(),
-            (
-                "Quordle",
-                50,
-                "",
-                "5️⃣4️⃣
-6️⃣7️⃣"
-            )
+            extract_wordlebot_command("!wordlebot  config thread_mode private "),
+            Some(("config", "thread_mode private"))
         );
+        assert_eq!(
+            extract_wordlebot_command("!wordlebot where public #spoilers"),
+            Some(("where", "public #spoilers"))
+        );
+        assert_eq!(extract_wordlebot_command("!wordlebot configure"), None);
+        assert_eq!(extract_wordlebot_command("!wordlebot"), None);
     }
 
     #[test]
-    fn test_octordle_regex() {
+    fn test_with_prefix() {
+        assert_eq!(with_prefix("!stats", "!").as_deref(), Some("!stats"));
+        assert_eq!(with_prefix("?stats", "?").as_deref(), Some("!stats"));
+        assert_eq!(with_prefix("!stats", "?"), None);
         assert_eq!(
-            extract_wordlelike_data(
-                "Daily Octordle 50
-6️⃣🔟
-4️⃣9️⃣
-7️⃣🕛
-5️⃣🕚"
-            )
-            .unwrap(),
-            (
-                "Octordle",
-                50,
-                "",
-                "6️⃣🔟
-4️⃣9️⃣
-7️⃣🕛
-5️⃣🕚"
-            )
+            with_prefix("!wordlebot config", "?").as_deref(),
+            Some("!wordlebot config")
         );
         assert_eq!(
-            extract_wordlelike_data(
-                "Daily Octordle #50
-6️⃣🔟
-4️⃣9️⃣
-7️⃣🕛
-5️⃣🕚"
-            )
-            .unwrap(),
-            (
-                "Octordle",
-                50,
-                "",
-                "6️⃣🔟
-4️⃣9️⃣
-7️⃣🕛
-5️⃣🕚"
-            )
+            with_prefix("?wordlebot config", "?").as_deref(),
+            Some("!wordlebot config")
         );
     }
 
     #[test]
-    fn test_redactle_regex() {
+    fn test_wordle_path() {
         assert_eq!(
-            extract_wordlelike_data(
-                "I solved today's Redactle (#31) in 159 guesses with an accuracy of 28.30%. Played at https://www.redactle.com/"
+            extract_wordle_path_query(
+                "!wordlepath abide
+Wordle 229 2/6
+⬛🟨🟨⬛⬛
+🟩🟩🟩🟩🟩"
             )
             .unwrap(),
             (
-                "Redactle",
-                31,
-                "",
-                "in 159 guesses with an accuracy of 28.30%. Played at https://www.redactle.com/"
+                Some("abide"),
+                "Wordle 229 2/6
+⬛🟨🟨⬛⬛
+🟩🟩🟩🟩🟩"
             )
         );
+        assert_eq!(
+            extract_wordle_path_query("!wordlepath\nWordle 229 1/6\n🟩🟩🟩🟩🟩"),
+            Some((None, "Wordle 229 1/6\n🟩🟩🟩🟩🟩"))
+        );
+        assert_eq!(extract_wordle_path_query("!stats"), None);
     }
 
-    #[test]
-    fn test_wordle_stats() {
+    fn handler() -> Handler {
+        Handler {
+            store: Arc::from(open_store("sqlite::memory:").unwrap()),
+            config: Config {
+                token: String::new(),
+                application_id: None,
+                store: "sqlite::memory:".to_string(),
+                recaps: vec![],
+                answers: Arc::new(answers::AnswerList::bundled()),
+                words_to_show: detector::DEFAULT_WORDS_TO_SHOW,
+                guilds: Default::default(),
+            },
+            threads: ThreadCache::default(),
+            commands_checked: AtomicBool::new(false),
+            newest_wordle: AtomicU32::new(0),
+        }
+    }
+
+    // From user 1 in channel 2, which is in guild 1 unless it's a DM
+    fn message(guild_id: Option<u64>, content: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": "10",
+            "channel_id": "2",
+            "guild_id": guild_id.map(|id| id.to_string()),
+            "author": {
+                "id": "1",
+                "username": "someone",
+                "discriminator": "0001",
+                "avatar": null
+            },
+            "content": content,
+            "timestamp": "2022-02-03T12:00:00+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0
+        }))
+        .unwrap()
+    }
+
+    const SHARE: &str = "Wordle 229 3/6\n\n⬛🟨⬛⬛⬛\n⬛🟩🟩⬛⬛\n🟩🟩🟩🟩🟩";
+
+    // Handle a share like a message would, telling whoever posted it about
+    // anything that went wrong. Gives back what kind of thing that was.
+    async fn post_share(
+        handler: &Handler,
+        platform: &FakePlatform,
+        msg: &Message,
+    ) -> Result<(), &'static str> {
+        let settings = guild_settings(handler.store.as_ref(), &handler.config, 1);
+        let (game, share) = parse_share(&msg.content).unwrap();
+        let handled = handler
+            .handle_share(platform, msg, &settings, game, &share)
+            .await;
+        match handled {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let kind = e.kind();
+                handler.report(platform, msg, "share", e).await;
+                Err(kind)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_share() {
+        let handler = handler();
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[(2, "general")]);
+        post_share(&handler, &platform, &message(Some(1), SHARE))
+            .await
+            .unwrap();
+        assert_eq!(handler.store.guild_results(1).unwrap().len(), 1);
+        assert_eq!(platform.threads.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_share_in_dm() {
+        let handler = handler();
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[(2, "general")]);
+        let handled = post_share(&handler, &platform, &message(None, SHARE)).await;
+        assert_eq!(handled, Err("no_guild"));
         assert_eq!(
-            extract_wordle_stats_query(
-                "!wordlestats
-train
-weigh
-slide
-oxide"
-            )
-            .unwrap(),
-            "train
-weigh
-slide
-oxide"
+            *platform.messages.lock().unwrap(),
+            [(
+                ChannelId(2),
+                "That only works in a server, not in DMs.".to_string()
+            )]
         );
+        assert!(platform.threads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_share_discord_error() {
+        let handler = handler();
+        // Discord doesn't know the channel
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let handled = post_share(&handler, &platform, &message(Some(1), SHARE)).await;
+        assert_eq!(handled, Err("http"));
+        // Nothing anyone in the channel can do about it, so nothing's said
+        assert!(platform.messages.lock().unwrap().is_empty());
+        // The result's still kept
+        assert_eq!(handler.store.guild_results(1).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_config_parse_error() {
+        let handler = handler();
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[(2, "general")]);
+        let msg = message(Some(1), "!wordlebot config spoiler_guard nope");
+        let settings = guild_settings(handler.store.as_ref(), &handler.config, 1);
+        let handled = handler
+            .configure(&platform, &msg, &settings, "spoiler_guard nope")
+            .await;
+        let e = handled.unwrap_err();
+        assert_eq!(e.kind(), "parse");
+        handler.report(&platform, &msg, "command", e).await;
+        let messages = platform.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, ChannelId(2));
+        assert!(messages[0].1.starts_with("Weird, spoiler_guard should be"));
     }
 }
@@ -0,0 +1,1091 @@
+use chrono::{DateTime, Duration, Utc};
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use serenity::{
+    async_trait,
+    http::{error::Error as HttpError, request::RequestBuilder, routing::RouteInfo, Http},
+    model::{
+        channel::{
+            ChannelType, GuildChannel, PermissionOverwrite, PermissionOverwriteType, ThreadsData,
+        },
+        guild::{PartialGuild, PremiumTier},
+        id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+        Permissions,
+    },
+};
+
+use crate::error::{needs, BotError};
+
+// A thread, without everything else discord says about it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Thread {
+    pub id: ChannelId,
+    pub guild_id: GuildId,
+    // The channel it's in
+    pub parent_id: Option<ChannelId>,
+    pub name: String,
+    pub kind: ChannelType,
+    // Gone quiet for long enough that discord's put it away
+    pub archived: bool,
+    // Only moderators can post in it
+    pub locked: bool,
+}
+
+impl From<GuildChannel> for Thread {
+    fn from(channel: GuildChannel) -> Thread {
+        Thread {
+            id: channel.id,
+            guild_id: channel.guild_id,
+            // Threads keep their channel's id where a channel would have its
+            // category
+            parent_id: channel.category_id,
+            name: channel.name,
+            kind: channel.kind,
+            archived: channel.thread_metadata.is_some_and(|m| m.archived),
+            locked: channel.thread_metadata.is_some_and(|m| m.locked),
+        }
+    }
+}
+
+// The bits of discord that finding and making solvers threads needs, so that
+// can be tested without discord.
+#[async_trait]
+pub trait ChatPlatform: Sync {
+    async fn active_threads(&self, guild_id: GuildId) -> Result<Vec<Thread>, BotError>;
+
+    // The channel's most recently archived threads of a kind. Forum posts
+    // count as public threads.
+    async fn archived_threads(
+        &self,
+        channel_id: ChannelId,
+        kind: ChannelType,
+    ) -> Result<Vec<Thread>, BotError>;
+
+    // Unarchived, and unlocked too if `unlock`
+    async fn reopen_thread(&self, thread_id: ChannelId, unlock: bool) -> Result<(), BotError>;
+
+    async fn create_thread(
+        &self,
+        channel_id: ChannelId,
+        name: &str,
+        kind: ChannelType,
+        archive_duration: Option<u16>,
+    ) -> Result<Thread, BotError>;
+
+    // A post in a forum channel, starting with `content` and tagged with
+    // `tag` (made if the forum doesn't have it yet)
+    async fn create_forum_post(
+        &self,
+        channel_id: ChannelId,
+        name: &str,
+        content: &str,
+        tag: &str,
+        archive_duration: Option<u16>,
+    ) -> Result<Thread, BotError>;
+
+    async fn post_message(&self, channel_id: ChannelId, content: &str) -> Result<(), BotError>;
+
+    // Posted as a reply to one of the channel's messages
+    async fn reply(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<(), BotError>;
+
+    // Posted without pinging anyone it mentions, then pinned
+    async fn post_pinned_message(
+        &self,
+        channel_id: ChannelId,
+        content: &str,
+    ) -> Result<(), BotError>;
+
+    // The pinned messages bots posted in the channel
+    async fn bot_pins(&self, channel_id: ChannelId) -> Result<Vec<(MessageId, String)>, BotError>;
+
+    async fn edit_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<(), BotError>;
+
+    // Nobody but moderators can post in it after this
+    async fn lock_thread(&self, thread_id: ChannelId) -> Result<(), BotError>;
+
+    async fn add_thread_member(
+        &self,
+        thread_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<(), BotError>;
+
+    async fn roles(&self, guild_id: GuildId) -> Result<Vec<(RoleId, String)>, BotError>;
+
+    async fn create_role(&self, guild_id: GuildId, name: &str) -> Result<RoleId, BotError>;
+
+    // Let the role see a channel, whoever else can't
+    async fn show_channel_to_role(
+        &self,
+        channel_id: ChannelId,
+        role_id: RoleId,
+    ) -> Result<(), BotError>;
+
+    async fn add_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Result<(), BotError>;
+
+    async fn delete_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<(), BotError>;
+
+    async fn premium_tier(&self, guild_id: GuildId) -> Result<PremiumTier, BotError>;
+
+    async fn find_channel(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Result<Option<ChannelId>, BotError>;
+
+    // What a guild channel's called, there's nothing to say about DMs
+    async fn channel_name(&self, channel_id: ChannelId) -> Result<String, BotError>;
+
+    // What a member with these roles can do anywhere in the guild
+    async fn member_permissions(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        roles: &[RoleId],
+    ) -> Result<Permissions, BotError>;
+
+    // What the bot's allowed to do in a channel
+    async fn bot_permissions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<Permissions, BotError>;
+}
+
+// Everything someone with these roles can do across the guild. Discord leaves
+// the sums to us without the cache.
+pub fn guild_permissions(guild: &PartialGuild, user_id: UserId, roles: &[RoleId]) -> Permissions {
+    if guild.owner_id == user_id {
+        return Permissions::all();
+    }
+    let permissions = guild
+        .roles
+        .values()
+        // @everyone has the guild's id
+        .filter(|role| role.id.0 == guild.id.0 || roles.contains(&role.id))
+        .fold(Permissions::empty(), |p, role| p | role.permissions);
+    if permissions.administrator() {
+        return Permissions::all();
+    }
+    permissions
+}
+
+// The guild's permissions with a channel's overwrites on top, @everyone's
+// first, then the roles', then the user's own
+pub fn channel_permissions(
+    guild_permissions: Permissions,
+    guild_id: GuildId,
+    user_id: UserId,
+    roles: &[RoleId],
+    overwrites: &[PermissionOverwrite],
+) -> Permissions {
+    if guild_permissions.administrator() {
+        return guild_permissions;
+    }
+    let mut permissions = guild_permissions;
+    let mut apply = |matches: &dyn Fn(&PermissionOverwriteType) -> bool| {
+        let (allow, deny) = overwrites
+            .iter()
+            .filter(|o| matches(&o.kind))
+            .fold((Permissions::empty(), Permissions::empty()), |(a, d), o| {
+                (a | o.allow, d | o.deny)
+            });
+        permissions = (permissions - deny) | allow;
+    };
+    apply(&|kind| matches!(kind, PermissionOverwriteType::Role(id) if id.0 == guild_id.0));
+    apply(
+        &|kind| matches!(kind, PermissionOverwriteType::Role(id) if id.0 != guild_id.0 && roles.contains(id)),
+    );
+    apply(&|kind| matches!(kind, PermissionOverwriteType::Member(id) if *id == user_id));
+    permissions
+}
+
+#[async_trait]
+impl ChatPlatform for Http {
+    async fn active_threads(&self, guild_id: GuildId) -> Result<Vec<Thread>, BotError> {
+        let threads = guild_id.get_active_threads(self).await?;
+        Ok(threads.threads.into_iter().map(Thread::from).collect())
+    }
+
+    async fn archived_threads(
+        &self,
+        channel_id: ChannelId,
+        kind: ChannelType,
+    ) -> Result<Vec<Thread>, BotError> {
+        let since = Utc::now() - Duration::days(ARCHIVED_LOOKBACK_DAYS);
+        let mut threads = Vec::new();
+        let mut before = None;
+        loop {
+            let page = archived_page(self, channel_id, kind, before.as_deref())
+                .await
+                .map_err(needs("Read Message History"))?;
+            before = page.threads.last().and_then(|last| {
+                let archived_at = last.thread_metadata.and_then(|m| m.archive_timestamp);
+                next_before(kind, last.id, archived_at, since)
+            });
+            threads.extend(page.threads.into_iter().map(Thread::from));
+            if !page.has_more || before.is_none() {
+                return Ok(threads);
+            }
+        }
+    }
+
+    async fn reopen_thread(&self, thread_id: ChannelId, unlock: bool) -> Result<(), BotError> {
+        // Only touching the lock when it's on, since that takes Manage Threads
+        thread_id
+            .edit_thread(self, |t| {
+                t.archived(false);
+                if unlock {
+                    t.locked(false);
+                }
+                t
+            })
+            .await
+            .map_err(needs(if unlock {
+                "Manage Threads"
+            } else {
+                "Send Messages in Threads"
+            }))?;
+        Ok(())
+    }
+
+    async fn create_thread(
+        &self,
+        channel_id: ChannelId,
+        name: &str,
+        kind: ChannelType,
+        archive_duration: Option<u16>,
+    ) -> Result<Thread, BotError> {
+        let thread = channel_id
+            .create_private_thread(self, |f| {
+                f.name(name);
+                f.kind(kind);
+                f.rate_limit_per_user(0);
+                if let Some(duration) = archive_duration {
+                    f.auto_archive_duration(duration);
+                }
+                f
+            })
+            .await
+            .map_err(needs(match kind {
+                ChannelType::PrivateThread => "Create Private Threads",
+                _ => "Create Public Threads",
+            }))?;
+        Ok(Thread::from(thread))
+    }
+
+    async fn create_forum_post(
+        &self,
+        channel_id: ChannelId,
+        name: &str,
+        content: &str,
+        tag: &str,
+        archive_duration: Option<u16>,
+    ) -> Result<Thread, BotError> {
+        // Serenity doesn't know about forums, but a post is made the same way
+        // as a thread with a message to start it off
+        let mut post = json!({
+            "name": name,
+            "message": { "content": content },
+        });
+        if let Some(duration) = archive_duration {
+            post["auto_archive_duration"] = json!(duration);
+        }
+        match forum_tag(self, channel_id, tag).await {
+            Ok(Some(tag_id)) => post["applied_tags"] = json!([tag_id.to_string()]),
+            Ok(None) => {}
+            // Better an untagged post than none
+            Err(e) => e.log("tag"),
+        }
+        let post = match post {
+            serde_json::Value::Object(post) => post,
+            _ => unreachable!(),
+        };
+        let thread = self
+            .create_private_thread(channel_id.0, &post)
+            .await
+            .map_err(needs("Send Messages"))?;
+        Ok(Thread::from(thread))
+    }
+
+    async fn post_message(&self, channel_id: ChannelId, content: &str) -> Result<(), BotError> {
+        channel_id
+            .say(self, content)
+            .await
+            .map_err(needs("Send Messages"))?;
+        Ok(())
+    }
+
+    async fn reply(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<(), BotError> {
+        channel_id
+            .send_message(self, |m| {
+                m.content(content)
+                    .reference_message((channel_id, message_id))
+            })
+            .await
+            .map_err(needs("Send Messages"))?;
+        Ok(())
+    }
+
+    async fn post_pinned_message(
+        &self,
+        channel_id: ChannelId,
+        content: &str,
+    ) -> Result<(), BotError> {
+        let message = channel_id
+            .send_message(self, |m| {
+                m.content(content);
+                m.allowed_mentions(|a| a.empty_parse());
+                m
+            })
+            .await
+            .map_err(needs("Send Messages"))?;
+        channel_id
+            .pin(self, message.id)
+            .await
+            .map_err(needs("Manage Messages"))?;
+        Ok(())
+    }
+
+    async fn bot_pins(&self, channel_id: ChannelId) -> Result<Vec<(MessageId, String)>, BotError> {
+        let pins = channel_id
+            .pins(self)
+            .await
+            .map_err(needs("Read Message History"))?;
+        Ok(pins
+            .into_iter()
+            .filter(|m| m.author.bot)
+            .map(|m| (m.id, m.content))
+            .collect())
+    }
+
+    async fn edit_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<(), BotError> {
+        channel_id
+            .edit_message(self, message_id, |m| m.content(content))
+            .await?;
+        Ok(())
+    }
+
+    async fn lock_thread(&self, thread_id: ChannelId) -> Result<(), BotError> {
+        thread_id
+            .edit_thread(self, |t| t.locked(true))
+            .await
+            .map_err(needs("Manage Threads"))?;
+        Ok(())
+    }
+
+    async fn add_thread_member(
+        &self,
+        thread_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<(), BotError> {
+        self.add_thread_channel_member(thread_id.0, user_id.0)
+            .await
+            .map_err(needs("Send Messages in Threads"))?;
+        Ok(())
+    }
+
+    async fn roles(&self, guild_id: GuildId) -> Result<Vec<(RoleId, String)>, BotError> {
+        let roles = guild_id.roles(self).await?;
+        Ok(roles
+            .into_iter()
+            .map(|(id, role)| (id, role.name))
+            .collect())
+    }
+
+    async fn create_role(&self, guild_id: GuildId, name: &str) -> Result<RoleId, BotError> {
+        let role = guild_id
+            .create_role(self, |r| r.name(name).mentionable(false))
+            .await
+            .map_err(needs("Manage Roles"))?;
+        Ok(role.id)
+    }
+
+    async fn show_channel_to_role(
+        &self,
+        channel_id: ChannelId,
+        role_id: RoleId,
+    ) -> Result<(), BotError> {
+        channel_id
+            .create_permission(
+                self,
+                &PermissionOverwrite {
+                    allow: Permissions::READ_MESSAGES,
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Role(role_id),
+                },
+            )
+            .await
+            .map_err(needs("Manage Channels"))?;
+        Ok(())
+    }
+
+    async fn add_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Result<(), BotError> {
+        Http::add_member_role(self, guild_id.0, user_id.0, role_id.0)
+            .await
+            .map_err(needs("Manage Roles"))?;
+        Ok(())
+    }
+
+    async fn delete_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<(), BotError> {
+        guild_id
+            .delete_role(self, role_id)
+            .await
+            .map_err(needs("Manage Roles"))?;
+        Ok(())
+    }
+
+    async fn premium_tier(&self, guild_id: GuildId) -> Result<PremiumTier, BotError> {
+        Ok(guild_id.to_partial_guild(self).await?.premium_tier)
+    }
+
+    async fn find_channel(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+    ) -> Result<Option<ChannelId>, BotError> {
+        let channels = guild_id.channels(self).await?;
+        Ok(channels.values().find(|c| c.name == name).map(|c| c.id))
+    }
+
+    async fn channel_name(&self, channel_id: ChannelId) -> Result<String, BotError> {
+        let channel = channel_id
+            .to_channel(self)
+            .await?
+            .guild()
+            .ok_or(BotError::NoGuild)?;
+        Ok(channel.name)
+    }
+
+    async fn member_permissions(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        roles: &[RoleId],
+    ) -> Result<Permissions, BotError> {
+        let guild = guild_id.to_partial_guild(self).await?;
+        Ok(guild_permissions(&guild, user_id, roles))
+    }
+
+    async fn bot_permissions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<Permissions, BotError> {
+        let me = self.get_current_user().await?;
+        let guild = guild_id.to_partial_guild(self).await?;
+        let member = guild_id.member(self, me.id).await?;
+        let channel = channel_id
+            .to_channel(self)
+            .await?
+            .guild()
+            .ok_or(BotError::NoGuild)?;
+        Ok(channel_permissions(
+            guild_permissions(&guild, me.id, &member.roles),
+            guild_id,
+            me.id,
+            &member.roles,
+            &channel.permission_overwrites,
+        ))
+    }
+}
+
+// Threads that were archived longer ago than this aren't worth reopening, so
+// there's no need to page back past them
+const ARCHIVED_LOOKBACK_DAYS: i64 = 7;
+
+// The most archived threads discord hands back at once
+const ARCHIVED_PAGE_SIZE: usize = 100;
+
+// Where discord's api is, for the requests serenity can't make itself
+const DISCORD_API: &str = "https://discord.com/api/v9";
+
+// How many more goes a rate limited request gets
+const RATE_LIMIT_RETRIES: usize = 3;
+
+lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::new();
+}
+
+// One page of a channel's archived threads, newest first. Serenity can't ask
+// for anything past the first page (it leaves the `?` off the query, and
+// public threads are paged by a timestamp it won't take), so this goes to
+// discord directly. That's around serenity's rate limiter, so being told to
+// slow down gets waited out here.
+async fn archived_page(
+    http: &Http,
+    channel_id: ChannelId,
+    kind: ChannelType,
+    before: Option<&str>,
+) -> Result<ThreadsData, serenity::Error> {
+    // The bot's in every private thread it made, so those are the only ones
+    // worth looking through
+    let which = match kind {
+        ChannelType::PrivateThread => "users/@me/threads/archived/private",
+        _ => "threads/archived/public",
+    };
+    let mut query = vec![("limit", ARCHIVED_PAGE_SIZE.to_string())];
+    if let Some(before) = before {
+        query.push(("before", before.to_string()));
+    }
+    let url = format!("{}/channels/{}/{}", DISCORD_API, channel_id.0, which);
+    let mut retries = RATE_LIMIT_RETRIES;
+    let response = loop {
+        let response = CLIENT
+            .get(&url)
+            .header("Authorization", &http.token)
+            .query(&query)
+            .send()
+            .await?;
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS || retries == 0 {
+            break response;
+        }
+        retries -= 1;
+        tokio::time::sleep(retry_after(response.headers())).await;
+    };
+    if !response.status().is_success() {
+        return Err(HttpError::from_response(response).await.into());
+    }
+    let body = response.bytes().await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+// How long discord wants us to wait before asking again, in seconds
+fn retry_after(headers: &reqwest::header::HeaderMap) -> std::time::Duration {
+    let seconds = headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .unwrap_or(1.0);
+    std::time::Duration::from_secs_f64(seconds)
+}
+
+// Where the next page of archived threads starts after `last`, or nothing if
+// `last` is already from before `since`. Private threads are paged by id,
+// public ones by when they were archived.
+fn next_before(
+    kind: ChannelType,
+    last: ChannelId,
+    archived_at: Option<DateTime<Utc>>,
+    since: DateTime<Utc>,
+) -> Option<String> {
+    match kind {
+        ChannelType::PrivateThread => (last.created_at() >= since).then(|| last.0.to_string()),
+        _ => archived_at
+            .filter(|archived_at| *archived_at >= since)
+            .map(|archived_at| archived_at.to_rfc3339()),
+    }
+}
+
+// Forums can have this many tags, no more
+const MAX_FORUM_TAGS: usize = 20;
+
+// The id of the forum's tag called `name`, adding it to the forum if it's not
+// there yet and there's room
+async fn forum_tag(
+    http: &Http,
+    channel_id: ChannelId,
+    name: &str,
+) -> Result<Option<u64>, BotError> {
+    let forum: Value = http
+        .fire(
+            RequestBuilder::new(RouteInfo::GetChannel {
+                channel_id: channel_id.0,
+            })
+            .build(),
+        )
+        .await?;
+    let mut tags = forum["available_tags"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if let Some(tag_id) = find_tag(&tags, name) {
+        return Ok(Some(tag_id));
+    }
+    if tags.len() >= MAX_FORUM_TAGS {
+        return Ok(None);
+    }
+    tags.push(json!({ "name": name }));
+    let body =
+        serde_json::to_vec(&json!({ "available_tags": tags })).map_err(serenity::Error::from)?;
+    let mut request = RequestBuilder::new(RouteInfo::EditChannel {
+        channel_id: channel_id.0,
+    });
+    request.body(Some(&body));
+    let forum: Value = http
+        .fire(request.build())
+        .await
+        .map_err(needs("Manage Channels"))?;
+    let tags = forum["available_tags"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    Ok(find_tag(&tags, name))
+}
+
+// Discord hands tags back as `{"id": "123", "name": "Wordle", ...}`
+fn find_tag(tags: &[Value], name: &str) -> Option<u64> {
+    let tag = tags.iter().find(|tag| {
+        tag["name"]
+            .as_str()
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+    })?;
+    tag["id"].as_str()?.parse().ok()
+}
+
+// Discord in memory, for tests
+#[cfg(test)]
+pub mod fake {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use serenity::{
+        async_trait,
+        model::{
+            channel::ChannelType,
+            guild::PremiumTier,
+            id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+            Permissions,
+        },
+    };
+
+    use super::{ChatPlatform, Thread};
+    use crate::error::BotError;
+
+    pub struct FakePlatform {
+        pub tier: PremiumTier,
+        pub channels: Vec<(ChannelId, String)>,
+        // Thread kinds we don't have permission to make
+        pub refused: Vec<ChannelType>,
+        pub permissions: Permissions,
+        pub threads: Mutex<Vec<Thread>>,
+        // A message's id is where it is in here
+        pub messages: Mutex<Vec<(ChannelId, String)>>,
+        pub pins: Mutex<Vec<MessageId>>,
+        pub thread_members: Mutex<Vec<(ChannelId, UserId)>>,
+        pub roles: Mutex<Vec<(RoleId, String)>>,
+        // Roles that can see a channel
+        pub shown: Mutex<Vec<(ChannelId, RoleId)>>,
+        pub member_roles: Mutex<Vec<(UserId, RoleId)>>,
+        // Forum posts and their tags
+        pub tags: Mutex<Vec<(ChannelId, String)>>,
+        // How many times the active threads have been asked for
+        pub lookups: AtomicUsize,
+    }
+
+    impl FakePlatform {
+        pub fn new(tier: PremiumTier, channels: &[(u64, &str)]) -> FakePlatform {
+            FakePlatform {
+                tier,
+                channels: channels
+                    .iter()
+                    .map(|(id, name)| (ChannelId(*id), name.to_string()))
+                    .collect(),
+                refused: vec![],
+                permissions: Permissions::all(),
+                threads: Mutex::new(vec![]),
+                messages: Mutex::new(vec![]),
+                pins: Mutex::new(vec![]),
+                thread_members: Mutex::new(vec![]),
+                roles: Mutex::new(vec![]),
+                shown: Mutex::new(vec![]),
+                member_roles: Mutex::new(vec![]),
+                tags: Mutex::new(vec![]),
+                lookups: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ChatPlatform for FakePlatform {
+        async fn active_threads(&self, guild_id: GuildId) -> Result<Vec<Thread>, BotError> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            let threads: Vec<_> = self
+                .threads
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| t.guild_id == guild_id && !t.archived)
+                .cloned()
+                .collect();
+            // Like waiting on discord for the answer, so anyone else posting
+            // gets a go before it arrives
+            tokio::task::yield_now().await;
+            Ok(threads)
+        }
+
+        async fn archived_threads(
+            &self,
+            channel_id: ChannelId,
+            kind: ChannelType,
+        ) -> Result<Vec<Thread>, BotError> {
+            let threads = self.threads.lock().unwrap();
+            Ok(threads
+                .iter()
+                .filter(|t| t.archived && t.parent_id == Some(channel_id) && t.kind == kind)
+                .cloned()
+                .collect())
+        }
+
+        async fn reopen_thread(&self, thread_id: ChannelId, unlock: bool) -> Result<(), BotError> {
+            let mut threads = self.threads.lock().unwrap();
+            for thread in threads.iter_mut().filter(|t| t.id == thread_id) {
+                thread.archived = false;
+                if unlock {
+                    thread.locked = false;
+                }
+            }
+            Ok(())
+        }
+
+        async fn create_thread(
+            &self,
+            channel_id: ChannelId,
+            name: &str,
+            kind: ChannelType,
+            _archive_duration: Option<u16>,
+        ) -> Result<Thread, BotError> {
+            if self.refused.contains(&kind) {
+                return Err(BotError::MissingPermission(Some("Create Private Threads")));
+            }
+            let mut threads = self.threads.lock().unwrap();
+            let thread = Thread {
+                id: ChannelId(1000 + threads.len() as u64),
+                guild_id: GuildId(1),
+                parent_id: Some(channel_id),
+                name: name.to_string(),
+                kind,
+                archived: false,
+                locked: false,
+            };
+            threads.push(thread.clone());
+            Ok(thread)
+        }
+
+        async fn create_forum_post(
+            &self,
+            channel_id: ChannelId,
+            name: &str,
+            content: &str,
+            tag: &str,
+            archive_duration: Option<u16>,
+        ) -> Result<Thread, BotError> {
+            let thread = self
+                .create_thread(
+                    channel_id,
+                    name,
+                    ChannelType::PublicThread,
+                    archive_duration,
+                )
+                .await?;
+            self.post_message(thread.id, content).await?;
+            let mut tags = self.tags.lock().unwrap();
+            tags.push((thread.id, tag.to_string()));
+            Ok(thread)
+        }
+
+        async fn post_message(&self, channel_id: ChannelId, content: &str) -> Result<(), BotError> {
+            let mut messages = self.messages.lock().unwrap();
+            messages.push((channel_id, content.to_string()));
+            Ok(())
+        }
+
+        async fn reply(
+            &self,
+            channel_id: ChannelId,
+            _message_id: MessageId,
+            content: &str,
+        ) -> Result<(), BotError> {
+            self.post_message(channel_id, content).await
+        }
+
+        async fn post_pinned_message(
+            &self,
+            channel_id: ChannelId,
+            content: &str,
+        ) -> Result<(), BotError> {
+            let mut messages = self.messages.lock().unwrap();
+            self.pins
+                .lock()
+                .unwrap()
+                .push(MessageId(messages.len() as u64));
+            messages.push((channel_id, content.to_string()));
+            Ok(())
+        }
+
+        async fn bot_pins(
+            &self,
+            channel_id: ChannelId,
+        ) -> Result<Vec<(MessageId, String)>, BotError> {
+            let messages = self.messages.lock().unwrap();
+            let pins = self.pins.lock().unwrap();
+            Ok(pins
+                .iter()
+                .map(|id| (*id, &messages[id.0 as usize]))
+                .filter(|(_, (channel, _))| *channel == channel_id)
+                .map(|(id, (_, content))| (id, content.clone()))
+                .collect())
+        }
+
+        async fn edit_message(
+            &self,
+            _channel_id: ChannelId,
+            message_id: MessageId,
+            content: &str,
+        ) -> Result<(), BotError> {
+            let mut messages = self.messages.lock().unwrap();
+            messages[message_id.0 as usize].1 = content.to_string();
+            Ok(())
+        }
+
+        async fn lock_thread(&self, thread_id: ChannelId) -> Result<(), BotError> {
+            let mut threads = self.threads.lock().unwrap();
+            for thread in threads.iter_mut().filter(|t| t.id == thread_id) {
+                thread.locked = true;
+            }
+            Ok(())
+        }
+
+        async fn add_thread_member(
+            &self,
+            thread_id: ChannelId,
+            user_id: UserId,
+        ) -> Result<(), BotError> {
+            let mut members = self.thread_members.lock().unwrap();
+            members.push((thread_id, user_id));
+            Ok(())
+        }
+
+        async fn roles(&self, _guild_id: GuildId) -> Result<Vec<(RoleId, String)>, BotError> {
+            Ok(self.roles.lock().unwrap().clone())
+        }
+
+        async fn create_role(&self, _guild_id: GuildId, name: &str) -> Result<RoleId, BotError> {
+            let mut roles = self.roles.lock().unwrap();
+            let role_id = RoleId(500 + roles.len() as u64);
+            roles.push((role_id, name.to_string()));
+            Ok(role_id)
+        }
+
+        async fn show_channel_to_role(
+            &self,
+            channel_id: ChannelId,
+            role_id: RoleId,
+        ) -> Result<(), BotError> {
+            self.shown.lock().unwrap().push((channel_id, role_id));
+            Ok(())
+        }
+
+        async fn add_member_role(
+            &self,
+            _guild_id: GuildId,
+            user_id: UserId,
+            role_id: RoleId,
+        ) -> Result<(), BotError> {
+            self.member_roles.lock().unwrap().push((user_id, role_id));
+            Ok(())
+        }
+
+        async fn delete_role(&self, _guild_id: GuildId, role_id: RoleId) -> Result<(), BotError> {
+            self.roles.lock().unwrap().retain(|(id, _)| *id != role_id);
+            self.shown.lock().unwrap().retain(|(_, id)| *id != role_id);
+            self.member_roles
+                .lock()
+                .unwrap()
+                .retain(|(_, id)| *id != role_id);
+            Ok(())
+        }
+
+        async fn premium_tier(&self, _guild_id: GuildId) -> Result<PremiumTier, BotError> {
+            Ok(self.tier)
+        }
+
+        async fn find_channel(
+            &self,
+            _guild_id: GuildId,
+            name: &str,
+        ) -> Result<Option<ChannelId>, BotError> {
+            Ok(self
+                .channels
+                .iter()
+                .find(|(_, channel)| channel == name)
+                .map(|(id, _)| *id))
+        }
+
+        // Discord doesn't know channels that aren't in here
+        async fn channel_name(&self, channel_id: ChannelId) -> Result<String, BotError> {
+            self.channels
+                .iter()
+                .find(|(id, _)| *id == channel_id)
+                .map(|(_, name)| name.clone())
+                .ok_or(BotError::Http(serenity::Error::Other("Unknown Channel")))
+        }
+
+        async fn member_permissions(
+            &self,
+            _guild_id: GuildId,
+            _user_id: UserId,
+            _roles: &[RoleId],
+        ) -> Result<Permissions, BotError> {
+            Ok(self.permissions)
+        }
+
+        async fn bot_permissions(
+            &self,
+            _guild_id: GuildId,
+            _channel_id: ChannelId,
+        ) -> Result<Permissions, BotError> {
+            Ok(self.permissions)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use serenity::model::{
+        channel::{ChannelType, PermissionOverwrite, PermissionOverwriteType},
+        id::{ChannelId, GuildId, RoleId, UserId},
+        Permissions,
+    };
+
+    use serde_json::json;
+
+    use super::{channel_permissions, find_tag, next_before, retry_after};
+
+    fn overwrite(
+        kind: PermissionOverwriteType,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> PermissionOverwrite {
+        PermissionOverwrite { allow, deny, kind }
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), std::time::Duration::from_secs(1));
+        headers.insert(reqwest::header::RETRY_AFTER, "2.5".parse().unwrap());
+        assert_eq!(
+            retry_after(&headers),
+            std::time::Duration::from_millis(2500)
+        );
+        headers.insert(reqwest::header::RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_next_before() {
+        let since = Utc.with_ymd_and_hms(2022, 4, 3, 12, 0, 0).unwrap();
+        let archived_at = Utc.with_ymd_and_hms(2022, 4, 9, 8, 30, 0).unwrap();
+        assert_eq!(
+            next_before(
+                ChannelType::PublicThread,
+                ChannelId(1),
+                Some(archived_at),
+                since
+            ),
+            Some("2022-04-09T08:30:00+00:00".to_string())
+        );
+        // Far enough back
+        assert_eq!(
+            next_before(
+                ChannelType::PublicThread,
+                ChannelId(1),
+                Some(since - Duration::hours(1)),
+                since
+            ),
+            None
+        );
+        // Private ones go by when they were made, which is in the id
+        let made = (archived_at.timestamp_millis() as u64 - 1_420_070_400_000) << 22;
+        assert_eq!(
+            next_before(ChannelType::PrivateThread, ChannelId(made), None, since),
+            Some(made.to_string())
+        );
+        assert_eq!(
+            next_before(ChannelType::PrivateThread, ChannelId(1), None, since),
+            None
+        );
+    }
+
+    #[test]
+    fn test_channel_permissions() {
+        let guild = GuildId(1);
+        let me = UserId(2);
+        let base = Permissions::SEND_MESSAGES | Permissions::CREATE_PUBLIC_THREADS;
+        let overwrites = [
+            // @everyone can't make threads here
+            overwrite(
+                PermissionOverwriteType::Role(RoleId(1)),
+                Permissions::empty(),
+                Permissions::CREATE_PUBLIC_THREADS | Permissions::SEND_MESSAGES,
+            ),
+            // but the bot role can
+            overwrite(
+                PermissionOverwriteType::Role(RoleId(3)),
+                Permissions::CREATE_PUBLIC_THREADS,
+                Permissions::empty(),
+            ),
+            overwrite(
+                PermissionOverwriteType::Member(me),
+                Permissions::MANAGE_THREADS,
+                Permissions::empty(),
+            ),
+        ];
+        assert_eq!(
+            channel_permissions(base, guild, me, &[RoleId(3)], &overwrites),
+            Permissions::CREATE_PUBLIC_THREADS | Permissions::MANAGE_THREADS
+        );
+        assert_eq!(
+            channel_permissions(base, guild, UserId(4), &[], &overwrites),
+            Permissions::empty()
+        );
+        // Admins can do anything
+        assert_eq!(
+            channel_permissions(Permissions::all(), guild, UserId(4), &[], &overwrites),
+            Permissions::all()
+        );
+    }
+
+    #[test]
+    fn test_find_tag() {
+        let tags = [
+            json!({ "id": "123", "name": "Wordle", "moderated": false }),
+            json!({ "id": "456", "name": "Heardle", "moderated": false }),
+        ];
+        assert_eq!(find_tag(&tags, "heardle"), Some(456));
+        assert_eq!(find_tag(&tags, "Quordle"), None);
+        assert_eq!(find_tag(&[], "Wordle"), None);
+    }
+}
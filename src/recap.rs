@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use serenity::{http::Http, model::id::ChannelId};
+
+use crate::games::game_or_default;
+use crate::stats::score_value;
+use crate::store::{GameResult, Score, Store};
+
+// How often to wake up and see if a recap is due
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+// Where the time comes from, so tests don't have to wait for it.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// Post a recap of yesterday's puzzles in a guild's channel every day at `time` (UTC).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecapSchedule {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub time: NaiveTime,
+}
+
+impl RecapSchedule {
+    // The last time at or before `now` the recap should have gone out
+    fn last_run(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = Utc.from_utc_datetime(&now.date_naive().and_time(self.time));
+        if today <= now {
+            today
+        } else {
+            today - Duration::days(1)
+        }
+    }
+
+    fn is_due(&self, last_check: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.last_run(now) > last_check
+    }
+}
+
+// Parse schedules like `<guild id>:<channel id>@<HH:MM>`, separated by commas.
+pub fn parse_schedules(config: &str) -> anyhow::Result<Vec<RecapSchedule>> {
+    config
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let bad_entry = || {
+                anyhow::anyhow!(
+                    "recap needs to look like <guild id>:<channel id>@<HH:MM>, got {}",
+                    entry
+                )
+            };
+            let (ids, time) = entry.split_once('@').ok_or_else(bad_entry)?;
+            let (guild_id, channel_id) = ids.split_once(':').ok_or_else(bad_entry)?;
+            Ok(RecapSchedule {
+                guild_id: guild_id.parse().map_err(|_| bad_entry())?,
+                channel_id: channel_id.parse().map_err(|_| bad_entry())?,
+                time: NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| bad_entry())?,
+            })
+        })
+        .collect()
+}
+
+// Each game's puzzle between `from` and `to`, the day most of the results
+// posted then were for. Anyone a timezone ahead or behind is on the one
+// either side, so that doesn't count.
+fn puzzle_days(results: &[GameResult], from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<(&str, u32)> {
+    let mut counts: BTreeMap<(&str, u32), usize> = BTreeMap::new();
+    for result in results
+        .iter()
+        .filter(|r| r.posted_at >= from.timestamp() && r.posted_at < to.timestamp())
+    {
+        *counts.entry((&result.game, result.day)).or_default() += 1;
+    }
+    let mut days: BTreeMap<&str, (u32, usize)> = BTreeMap::new();
+    for ((game, day), count) in counts {
+        // Ties go to the earlier day
+        let best = days.entry(game).or_insert((day, count));
+        if count > best.1 {
+            *best = (day, count);
+        }
+    }
+    days.into_iter()
+        .map(|(game, (day, _))| (game, day))
+        .collect()
+}
+
+// Summarize the puzzles played between `from` and `to`, one line per puzzle.
+// Everyone's result for the puzzle counts, whenever they posted it. None if
+// nobody played.
+pub fn build_recap(
+    results: &[GameResult],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<String> {
+    let mut puzzles: BTreeMap<(&str, u32), Vec<&GameResult>> = BTreeMap::new();
+    for puzzle in puzzle_days(results, from, to) {
+        puzzles.insert(
+            puzzle,
+            results
+                .iter()
+                .filter(|r| (r.game.as_str(), r.day) == puzzle)
+                .collect(),
+        );
+    }
+    if puzzles.is_empty() {
+        return None;
+    }
+
+    let mention_all = |results: &[&&GameResult]| {
+        results
+            .iter()
+            .map(|r| format!("<@{}>", r.user_id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut recap = "Yesterday's puzzles:".to_string();
+    for ((game, day), results) in puzzles.iter() {
+        let scoring = game_or_default(game);
+        recap.push_str(&format!("\n**{} {}** {} played", game, day, results.len()));
+        let best = results
+            .iter()
+            .filter_map(|r| match r.score {
+                Score::Solved(guesses) => Some(guesses),
+                _ => None,
+            })
+            .min();
+        if let Some(best) = best {
+            let best_players: Vec<_> = results
+                .iter()
+                .filter(|r| r.score == Score::Solved(best))
+                .collect();
+            recap.push_str(&format!(
+                ", best {} by {}",
+                scoring.score_text(Score::Solved(best)),
+                mention_all(&best_players)
+            ));
+        }
+        let failed: Vec<_> = results
+            .iter()
+            .filter(|r| r.score == Score::Failed)
+            .collect();
+        if !failed.is_empty() {
+            recap.push_str(&format!(
+                ", {} for {}",
+                scoring.score_text(Score::Failed),
+                mention_all(&failed)
+            ));
+        }
+        let scores: Vec<f64> = results.iter().filter_map(|r| score_value(r)).collect();
+        if !scores.is_empty() {
+            recap.push_str(&format!(
+                ", average {:.2}",
+                scores.iter().sum::<f64>() / scores.len() as f64
+            ));
+        }
+        let everyone: Vec<_> = results.iter().collect();
+        recap.push_str(&format!("\nPlayed: {}", mention_all(&everyone)));
+    }
+    Some(recap)
+}
+
+// Works out which recaps are due each time it's ticked.
+pub struct Recapper {
+    store: Arc<dyn Store>,
+    schedules: Vec<RecapSchedule>,
+    last_check: DateTime<Utc>,
+}
+
+impl Recapper {
+    pub fn new(
+        store: Arc<dyn Store>,
+        schedules: Vec<RecapSchedule>,
+        now: DateTime<Utc>,
+    ) -> Recapper {
+        Recapper {
+            store,
+            schedules,
+            last_check: now,
+        }
+    }
+
+    // Every recap that came due since the last tick, as (channel id, message).
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Vec<(u64, String)> {
+        let mut recaps = vec![];
+        for schedule in self.schedules.iter() {
+            if !schedule.is_due(self.last_check, now) {
+                continue;
+            }
+            let results = match self.store.guild_results(schedule.guild_id) {
+                Ok(results) => results,
+                Err(e) => {
+                    println!("Error loading results for recap: {:?}", e);
+                    continue;
+                }
+            };
+            let today = Utc
+                .from_utc_datetime(&schedule.last_run(now).date_naive().and_time(NaiveTime::MIN));
+            if let Some(recap) = build_recap(&results, today - Duration::days(1), today) {
+                recaps.push((schedule.channel_id, recap));
+            }
+        }
+        self.last_check = now;
+        recaps
+    }
+}
+
+pub async fn run_recaps(http: Arc<Http>, mut recapper: Recapper, clock: impl Clock) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        for (channel_id, recap) in recapper.tick(clock.now()) {
+            let sent = ChannelId(channel_id)
+                .send_message(&http, |m| {
+                    m.content(recap);
+                    // Nobody needs a ping every morning
+                    m.allowed_mentions(|am| am.empty_parse());
+                    m
+                })
+                .await;
+            if let Err(e) = sent {
+                println!("Error posting recap: {:?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_recap, parse_schedules, RecapSchedule, Recapper};
+    use crate::store::{test_result, FileStore, GameResult, Score, Store};
+    use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 4, day, hour, minute, 0).unwrap()
+    }
+
+    fn result(
+        user_id: u64,
+        game: &str,
+        day: u32,
+        score: Score,
+        posted: DateTime<Utc>,
+    ) -> GameResult {
+        GameResult {
+            posted_at: posted.timestamp(),
+            ..test_result(user_id, game, day, score)
+        }
+    }
+
+    #[test]
+    fn test_parse_schedules() {
+        assert_eq!(
+            parse_schedules("1:2@09:30, 3:4@23:00").unwrap(),
+            vec![
+                RecapSchedule {
+                    guild_id: 1,
+                    channel_id: 2,
+                    time: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+                },
+                RecapSchedule {
+                    guild_id: 3,
+                    channel_id: 4,
+                    time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                },
+            ]
+        );
+        assert!(parse_schedules("").unwrap().is_empty());
+        assert!(parse_schedules("1:2").is_err());
+        assert!(parse_schedules("1@09:30").is_err());
+        assert!(parse_schedules("1:2@9am").is_err());
+    }
+
+    #[test]
+    fn test_schedule_is_due() {
+        let schedule = parse_schedules("1:2@09:30").unwrap().remove(0);
+        assert!(!schedule.is_due(at(10, 9, 0), at(10, 9, 29)));
+        assert!(schedule.is_due(at(10, 9, 29), at(10, 9, 30)));
+        assert!(schedule.is_due(at(10, 9, 29), at(10, 9, 31)));
+        assert!(!schedule.is_due(at(10, 9, 30), at(10, 9, 31)));
+        assert!(!schedule.is_due(at(10, 9, 31), at(11, 9, 0)));
+        // Asleep for a few days, only catches up once
+        assert!(schedule.is_due(at(7, 12, 0), at(10, 12, 0)));
+    }
+
+    #[test]
+    fn test_build_recap() {
+        let results = vec![
+            result(1, "Wordle", 229, Score::Solved(3), at(9, 8, 0)),
+            result(2, "Wordle", 229, Score::Solved(5), at(9, 12, 0)),
+            result(3, "Wordle", 229, Score::Failed, at(9, 23, 0)),
+            result(4, "Wordle", 229, Score::Solved(3), at(9, 23, 59)),
+            result(1, "Heardle", 16, Score::Unscored, at(9, 10, 0)),
+            result(2, "Quordle", 50, Score::Solved(22), at(9, 10, 0)),
+            result(3, "Quordle", 50, Score::Failed, at(9, 11, 0)),
+            // Today's and the day before's don't count
+            result(1, "Wordle", 230, Score::Solved(1), at(10, 0, 0)),
+            result(1, "Wordle", 228, Score::Solved(1), at(8, 23, 59)),
+            // Even from someone a timezone ahead, who's already on today's
+            result(6, "Wordle", 230, Score::Solved(2), at(9, 23, 0)),
+            // But someone a timezone behind still gets in
+            result(5, "Wordle", 229, Score::Solved(6), at(10, 3, 0)),
+        ];
+        assert_eq!(
+            build_recap(&results, at(9, 0, 0), at(10, 0, 0)).unwrap(),
+            "Yesterday's puzzles:
+**Heardle 16** 1 played
+Played: <@1>
+**Quordle 50** 2 played, best 22 by <@2>, X for <@3>, average 29.50
+Played: <@2>, <@3>
+**Wordle 229** 5 played, best 3/6 by <@1>, <@4>, X/6 for <@3>, average 4.80
+Played: <@1>, <@2>, <@3>, <@4>, <@5>"
+        );
+        assert_eq!(build_recap(&results, at(11, 0, 0), at(12, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_recapper() {
+        let path = std::env::temp_dir().join(format!("wordlebot-{}-recap", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = Arc::new(FileStore::open(&path).unwrap());
+        store
+            .record_result(&result(1, "Wordle", 229, Score::Solved(3), at(9, 8, 0)))
+            .unwrap();
+        let schedules = parse_schedules("1:2@09:30, 5:6@09:30, 1:7@18:00").unwrap();
+        let mut recapper = Recapper::new(store, schedules, at(10, 9, 0));
+
+        assert!(recapper.tick(at(10, 9, 29)).is_empty());
+        // Guild 5 has nothing to recap
+        let recaps = recapper.tick(at(10, 9, 30));
+        assert_eq!(recaps.len(), 1);
+        assert_eq!(recaps[0].0, 2);
+        assert!(recaps[0].1.contains("**Wordle 229** 1 played"));
+        assert!(recapper.tick(at(10, 9, 31)).is_empty());
+        assert_eq!(recapper.tick(at(10, 18, 0))[0].0, 7);
+        // Nothing posted on the 10th, so nothing for the 11th's recap
+        assert!(recapper.tick(at(11, 9, 30)).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId};
+
+use crate::config::{Config, SpoilerPolicy};
+use crate::error::BotError;
+use crate::games::find_game;
+use crate::gate::GateMode;
+use crate::guard::GuardAction;
+use crate::store::Store;
+use crate::threads::{parse_archive_duration, Placement, ThreadMode, ThreadStrategy};
+
+const DEFAULT_PREFIX: &str = "!";
+const DEFAULT_SOLVERS_CHANNEL: &str = "{channel}_solvers";
+
+// The settings `!wordlebot config` can change
+pub const KEYS: &[&str] = &[
+    "prefix",
+    "slash_only",
+    "games",
+    "thread_mode",
+    "solvers_channel",
+    "thread_gate",
+    "thread_archive",
+    "spoiler_policy",
+    "spoiler_guard",
+];
+
+// How a guild wants the bot to behave. Anything left unset falls back to the
+// config file, and then to the defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuildSettings {
+    // What the text commands start with instead of !
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    // Moved over to slash commands, the text ones just get a hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slash_only: Option<bool>,
+    // The games that get threads, all of them if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub games: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_mode: Option<ThreadMode>,
+    // Where public threads go, with {channel} for the channel the result was
+    // posted in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solvers_channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_gate: Option<GateMode>,
+    // 1h, 1d, 3d or 1w
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_archive: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spoiler_policy: Option<SpoilerPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spoiler_guard: Option<GuardAction>,
+    // Where each channel's threads go, set with `!wordlebot where`. Channels
+    // without one go by thread_mode. By channel id, as a string since that's
+    // all toml allows for keys.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub channels: BTreeMap<String, ThreadStrategy>,
+}
+
+impl GuildSettings {
+    // These settings, with anything unset taken from `fallback`
+    pub fn or(self, fallback: &GuildSettings) -> GuildSettings {
+        let fallback = fallback.clone();
+        let mut channels = fallback.channels;
+        channels.extend(self.channels);
+        GuildSettings {
+            prefix: self.prefix.or(fallback.prefix),
+            slash_only: self.slash_only.or(fallback.slash_only),
+            games: self.games.or(fallback.games),
+            thread_mode: self.thread_mode.or(fallback.thread_mode),
+            solvers_channel: self.solvers_channel.or(fallback.solvers_channel),
+            thread_gate: self.thread_gate.or(fallback.thread_gate),
+            thread_archive: self.thread_archive.or(fallback.thread_archive),
+            spoiler_policy: self.spoiler_policy.or(fallback.spoiler_policy),
+            spoiler_guard: self.spoiler_guard.or(fallback.spoiler_guard),
+            channels,
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or(DEFAULT_PREFIX)
+    }
+
+    pub fn slash_only(&self) -> bool {
+        self.slash_only.unwrap_or(false)
+    }
+
+    pub fn game_enabled(&self, game: &str) -> bool {
+        match &self.games {
+            Some(games) => games.iter().any(|g| g.eq_ignore_ascii_case(game)),
+            None => true,
+        }
+    }
+
+    pub fn thread_mode(&self) -> ThreadMode {
+        self.thread_mode.unwrap_or(ThreadMode::Auto)
+    }
+
+    // The solvers channel for results posted in `channel`
+    pub fn solvers_channel(&self, channel: &str) -> String {
+        self.solvers_channel
+            .as_deref()
+            .unwrap_or(DEFAULT_SOLVERS_CHANNEL)
+            .replace("{channel}", channel)
+    }
+
+    // Where threads for results posted in a channel go, nothing to go by the
+    // guild's boost level
+    pub fn thread_strategy(&self, channel_id: u64, channel: &str) -> Option<ThreadStrategy> {
+        if let Some(strategy) = self.channels.get(&channel_id.to_string()) {
+            return Some(strategy.clone());
+        }
+        match self.thread_mode() {
+            ThreadMode::Auto => None,
+            ThreadMode::Private => Some(ThreadStrategy::Private),
+            ThreadMode::Public => Some(ThreadStrategy::Public(Some(self.solvers_channel(channel)))),
+        }
+    }
+
+    // How the guild wants threads for a result posted in a channel
+    pub fn placement(&self, guild_id: GuildId, channel_id: ChannelId, channel: &str) -> Placement {
+        Placement {
+            guild_id,
+            channel_id,
+            strategy: self.thread_strategy(channel_id.0, channel),
+            solvers_channel: self.solvers_channel(channel),
+            gate: self.thread_gate(),
+            archive_duration: self.archive_duration(),
+        }
+    }
+
+    pub fn thread_gate(&self) -> GateMode {
+        self.thread_gate.unwrap_or(GateMode::Open)
+    }
+
+    // In minutes, discord's default of a day if unset
+    pub fn archive_duration(&self) -> Option<u16> {
+        self.thread_archive
+            .as_deref()
+            .and_then(parse_archive_duration)
+    }
+
+    pub fn spoiler_policy(&self) -> SpoilerPolicy {
+        self.spoiler_policy.unwrap_or(SpoilerPolicy::Private)
+    }
+
+    pub fn spoiler_guard(&self) -> GuardAction {
+        self.spoiler_guard.unwrap_or(GuardAction::Off)
+    }
+
+    // Checks a setting from the config file, so a typo stops the bot starting
+    // rather than being quietly ignored
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(prefix) = &self.prefix {
+            parse_prefix(prefix)?;
+        }
+        if let Some(games) = &self.games {
+            parse_games(&games.join(","))?;
+        }
+        if let Some(duration) = &self.thread_archive {
+            parse_archive_duration(duration)
+                .ok_or_else(|| format!("{} isn't one of 1h, 1d, 3d or 1w", duration))?;
+        }
+        for channel_id in self.channels.keys() {
+            channel_id
+                .parse::<u64>()
+                .map_err(|_| format!("expected a channel id, got {}", channel_id))?;
+        }
+        Ok(())
+    }
+
+    // `<key> <value>` or `<key> reset` from `!wordlebot config`, giving back
+    // what changed
+    pub fn apply(&mut self, args: &str) -> Result<String, String> {
+        let args = args.trim();
+        let (key, value) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let value = value.trim();
+        if !KEYS.contains(&key) {
+            return Err(format!(
+                "I don't have a {} setting, try one of {}",
+                key,
+                KEYS.join(", ")
+            ));
+        }
+        if value.is_empty() {
+            return Err(format!(
+                "what should {} be? Or `reset` to go back to the default",
+                key
+            ));
+        }
+        if value.eq_ignore_ascii_case("reset") {
+            self.set(key, None)?;
+            return Ok(format!("Reset {}", key));
+        }
+        self.set(key, Some(value))?;
+        Ok(format!("Set {} to {}", key, value))
+    }
+
+    fn set(&mut self, key: &str, value: Option<&str>) -> Result<(), String> {
+        let bad = |expected: &str| format!("{} should be {}", key, expected);
+        match key {
+            "prefix" => self.prefix = value.map(parse_prefix).transpose()?,
+            "slash_only" => {
+                self.slash_only = value
+                    .map(|v| parse_bool(v).ok_or_else(|| bad("on or off")))
+                    .transpose()?
+            }
+            "games" => self.games = value.map(parse_games).transpose()?,
+            "thread_mode" => {
+                self.thread_mode = value
+                    .map(|v| ThreadMode::parse(v).ok_or_else(|| bad("auto, private or public")))
+                    .transpose()?
+            }
+            "solvers_channel" => {
+                self.solvers_channel = value.map(|v| v.trim_start_matches('#').to_string())
+            }
+            "thread_gate" => {
+                self.thread_gate = value
+                    .map(|v| GateMode::parse(v).ok_or_else(|| bad("open, members or role")))
+                    .transpose()?
+            }
+            "thread_archive" => {
+                self.thread_archive = value
+                    .map(|v| match parse_archive_duration(v) {
+                        Some(_) => Ok(v.to_lowercase()),
+                        None => Err(bad("1h, 1d, 3d or 1w")),
+                    })
+                    .transpose()?
+            }
+            "spoiler_policy" => {
+                self.spoiler_policy = value
+                    .map(|v| {
+                        SpoilerPolicy::parse(v).ok_or_else(|| bad("private, thread or spoiler"))
+                    })
+                    .transpose()?
+            }
+            "spoiler_guard" => {
+                self.spoiler_guard = value
+                    .map(|v| {
+                        GuardAction::parse(v).ok_or_else(|| bad("off, delete, repost or warn"))
+                    })
+                    .transpose()?
+            }
+            _ => unreachable!("checked against KEYS"),
+        }
+        Ok(())
+    }
+
+    // Every setting and what it's set to, defaults included
+    pub fn describe(&self) -> String {
+        let games = match &self.games {
+            Some(games) => games.join(", "),
+            None => "all".to_string(),
+        };
+        let archive = self.thread_archive.as_deref().unwrap_or("1d");
+        [
+            ("prefix", self.prefix().to_string()),
+            (
+                "slash_only",
+                if self.slash_only() { "on" } else { "off" }.to_string(),
+            ),
+            ("games", games),
+            ("thread_mode", name(self.thread_mode())),
+            ("solvers_channel", self.solvers_channel("{channel}")),
+            ("thread_gate", name(self.thread_gate())),
+            ("thread_archive", archive.to_string()),
+            ("spoiler_policy", name(self.spoiler_policy())),
+            ("spoiler_guard", name(self.spoiler_guard())),
+        ]
+        .iter()
+        .map(|(key, value)| format!("`{}`: {}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+}
+
+// The name a setting's value goes by in the config file
+fn name(value: impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => "?".to_string(),
+    }
+}
+
+fn parse_prefix(prefix: &str) -> Result<String, String> {
+    if prefix.is_empty() || prefix.len() > 3 || prefix.contains(char::is_whitespace) {
+        return Err(format!(
+            "{} won't work as a prefix, try something like ?",
+            prefix
+        ));
+    }
+    Ok(prefix.to_string())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "yes" => Some(true),
+        "off" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+// A comma separated list of game names, giving back how the games spell them
+fn parse_games(games: &str) -> Result<Vec<String>, String> {
+    games
+        .split(',')
+        .map(str::trim)
+        .filter(|game| !game.is_empty())
+        .map(|game| match find_game(game) {
+            Some(game) => Ok(game.name().to_string()),
+            None => Err(format!("I don't know a game called {}", game)),
+        })
+        .collect()
+}
+
+// A guild's settings, anything changed with `!wordlebot config` over the
+// config file
+pub fn guild_settings(store: &dyn Store, config: &Config, guild_id: u64) -> GuildSettings {
+    let configured = config.guilds.get(&guild_id).cloned().unwrap_or_default();
+    match store.guild_settings(guild_id) {
+        Ok(stored) => stored.or(&configured),
+        Err(e) => {
+            BotError::Store(e).log("settings");
+            configured
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuildSettings;
+    use crate::config::SpoilerPolicy;
+    use crate::gate::GateMode;
+    use crate::guard::GuardAction;
+    use crate::threads::{ThreadMode, ThreadStrategy};
+
+    #[test]
+    fn test_defaults() {
+        let settings = GuildSettings::default();
+        assert_eq!(settings.prefix(), "!");
+        assert!(!settings.slash_only());
+        assert!(settings.game_enabled("Heardle"));
+        assert_eq!(settings.thread_mode(), ThreadMode::Auto);
+        assert_eq!(settings.solvers_channel("general"), "general_solvers");
+        assert_eq!(settings.thread_gate(), GateMode::Open);
+        assert_eq!(settings.archive_duration(), None);
+        assert_eq!(settings.spoiler_policy(), SpoilerPolicy::Private);
+        assert_eq!(settings.spoiler_guard(), GuardAction::Off);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut settings = GuildSettings::default();
+        assert_eq!(settings.apply("prefix ?").unwrap(), "Set prefix to ?");
+        assert_eq!(settings.prefix(), "?");
+        settings.apply("games wordle, HEARDLE").unwrap();
+        assert_eq!(
+            settings.games,
+            Some(vec!["Wordle".to_string(), "Heardle".to_string()])
+        );
+        assert!(settings.game_enabled("Heardle"));
+        assert!(!settings.game_enabled("Quordle"));
+        settings
+            .apply("solvers_channel #spoilers-{channel}")
+            .unwrap();
+        assert_eq!(settings.solvers_channel("general"), "spoilers-general");
+        settings.apply("thread_mode Private").unwrap();
+        assert_eq!(settings.thread_mode(), ThreadMode::Private);
+        settings.apply("thread_archive 1W").unwrap();
+        assert_eq!(settings.archive_duration(), Some(10080));
+        settings.apply("slash_only on").unwrap();
+        assert!(settings.slash_only());
+        settings.apply("spoiler_guard repost").unwrap();
+        assert_eq!(settings.spoiler_guard(), GuardAction::Repost);
+
+        assert_eq!(settings.apply("prefix reset").unwrap(), "Reset prefix");
+        assert_eq!(settings.prefix, None);
+
+        // Bad values leave the setting alone
+        assert!(settings.apply("thread_mode secret").is_err());
+        assert_eq!(settings.thread_mode(), ThreadMode::Private);
+        assert!(settings.apply("games wordle, chess").is_err());
+        assert!(settings.apply("thread_archive 2d").is_err());
+        assert!(settings.apply("prefix").is_err());
+        assert!(settings.apply("colour blue").is_err());
+    }
+
+    #[test]
+    fn test_or() {
+        let mut stored = GuildSettings::default();
+        stored.apply("spoiler_policy thread").unwrap();
+        let mut configured = GuildSettings::default();
+        configured.apply("spoiler_policy spoiler").unwrap();
+        configured.apply("thread_gate members").unwrap();
+        let settings = stored.or(&configured);
+        assert_eq!(settings.spoiler_policy(), SpoilerPolicy::Thread);
+        assert_eq!(settings.thread_gate(), GateMode::Members);
+    }
+
+    #[test]
+    fn test_thread_strategy() {
+        let mut settings = GuildSettings::default();
+        assert_eq!(settings.thread_strategy(1, "general"), None);
+        settings.apply("thread_mode public").unwrap();
+        assert_eq!(
+            settings.thread_strategy(1, "general"),
+            Some(ThreadStrategy::Public(Some("general_solvers".to_string())))
+        );
+        settings
+            .channels
+            .insert("1".to_string(), ThreadStrategy::Off);
+        assert_eq!(
+            settings.thread_strategy(1, "general"),
+            Some(ThreadStrategy::Off)
+        );
+
+        // Channels set in discord go on top of the config file's
+        let mut configured = GuildSettings::default();
+        configured
+            .channels
+            .insert("1".to_string(), ThreadStrategy::Private);
+        configured
+            .channels
+            .insert("2".to_string(), ThreadStrategy::Private);
+        let settings = settings.or(&configured);
+        assert_eq!(
+            settings.thread_strategy(1, "general"),
+            Some(ThreadStrategy::Off)
+        );
+        assert_eq!(
+            settings.thread_strategy(2, "random"),
+            Some(ThreadStrategy::Private)
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut settings = GuildSettings::default();
+        settings.apply("spoiler_policy dm").unwrap();
+        let description = settings.describe();
+        assert!(description.contains("`prefix`: !"));
+        assert!(description.contains("`games`: all"));
+        assert!(description.contains("`solvers_channel`: {channel}_solvers"));
+        assert!(description.contains("`spoiler_policy`: private"));
+        assert!(description.contains("`thread_archive`: 1d"));
+    }
+}
@@ -0,0 +1,411 @@
+use chrono::Utc;
+use serde_json::Value;
+use serenity::{
+    builder::CreateApplicationCommands,
+    http::{request::RequestBuilder, routing::RouteInfo, Http},
+    model::interactions::{
+        application_command::{
+            ApplicationCommandInteraction, ApplicationCommandInteractionDataOption,
+            ApplicationCommandOptionType,
+        },
+        autocomplete::AutocompleteInteraction,
+        InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+    },
+    prelude::Mentionable,
+};
+
+use crate::cache::ThreadCache;
+use crate::commands::{self, Reply};
+use crate::config::{Config, SpoilerPolicy};
+use crate::error::BotError;
+use crate::platform::ChatPlatform;
+use crate::settings::guild_settings;
+use crate::stats::Window;
+use crate::store::Store;
+use crate::threads::latest_solvers_thread;
+
+// Discord only shows this many autocomplete choices
+const MAX_CHOICES: usize = 25;
+
+fn command_definitions() -> Vec<Value> {
+    let mut cmds = CreateApplicationCommands::default();
+    cmds.create_application_command(|c| {
+        c.name("wordlestats")
+            .description("How many words were left after each of your guesses")
+            .create_option(|o| {
+                o.name("guesses")
+                    .description(
+                        "Your guesses separated by spaces, answer last or the wordle number first",
+                    )
+                    .kind(ApplicationCommandOptionType::String)
+                    .required(true)
+            })
+    });
+    cmds.create_application_command(|c| {
+        c.name("wordlepath")
+            .description("How many words could have made each row of your grid")
+            .create_option(|o| {
+                o.name("grid")
+                    .description("Your shared grid, rows separated by spaces")
+                    .kind(ApplicationCommandOptionType::String)
+                    .required(true)
+            })
+            .create_option(|o| {
+                o.name("answer")
+                    .description("The answer or the wordle number, today's by default")
+                    .kind(ApplicationCommandOptionType::String)
+            })
+    });
+    cmds.create_application_command(|c| {
+        c.name("stats")
+            .description("Games played, streaks and guess distribution")
+            .create_option(|o| {
+                o.name("user")
+                    .description("Whose stats, yours by default")
+                    .kind(ApplicationCommandOptionType::User)
+            })
+            .create_option(|o| {
+                o.name("game")
+                    .description("Which game, Wordle by default")
+                    .kind(ApplicationCommandOptionType::String)
+                    .set_autocomplete(true)
+            })
+    });
+    cmds.create_application_command(|c| {
+        c.name("leaderboard")
+            .description("Rank everyone in the server")
+            .create_option(|o| {
+                o.name("game")
+                    .description("Which game, Wordle by default")
+                    .kind(ApplicationCommandOptionType::String)
+                    .set_autocomplete(true)
+            })
+            .create_option(|o| {
+                o.name("window")
+                    .description("How far back to look, all time by default")
+                    .kind(ApplicationCommandOptionType::String)
+                    .add_string_choice("week", "week")
+                    .add_string_choice("month", "month")
+                    .add_string_choice("all", "all")
+            })
+    });
+    cmds.0
+}
+
+// Whether discord's copy of a command has everything the definition sets.
+// Discord adds ids and versions of its own and leaves out false flags.
+fn matches_definition(defined: &Value, registered: &Value) -> bool {
+    match (defined, registered) {
+        (Value::Object(defined), Value::Object(registered)) => {
+            defined
+                .iter()
+                .all(|(key, value)| match registered.get(key) {
+                    Some(registered) => matches_definition(value, registered),
+                    None => *value == Value::Bool(false),
+                })
+        }
+        (Value::Array(defined), Value::Array(registered)) => {
+            defined.len() == registered.len()
+                && defined
+                    .iter()
+                    .zip(registered)
+                    .all(|(d, r)| matches_definition(d, r))
+        }
+        _ => defined == registered,
+    }
+}
+
+// Commands come back from discord in any order
+fn commands_up_to_date(defined: &[Value], registered: &[Value]) -> bool {
+    defined.len() == registered.len()
+        && defined.iter().all(|d| {
+            registered
+                .iter()
+                .any(|r| r["name"] == d["name"] && matches_definition(d, r))
+        })
+}
+
+// Setting the commands is rate limited, so they're only sent when discord's
+// out of date. Says whether they were.
+pub async fn register_commands(http: &Http) -> Result<bool, BotError> {
+    let defined = command_definitions();
+    let registered: Vec<Value> = http
+        .fire(
+            RequestBuilder::new(RouteInfo::GetGlobalApplicationCommands {
+                application_id: http.application_id,
+            })
+            .build(),
+        )
+        .await?;
+    if commands_up_to_date(&defined, &registered) {
+        return Ok(false);
+    }
+    http.create_global_application_commands(&Value::Array(defined))
+        .await?;
+    Ok(true)
+}
+
+fn option_str<'a>(
+    options: &'a [ApplicationCommandInteractionDataOption],
+    name: &str,
+) -> Option<&'a str> {
+    options
+        .iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_ref())
+        .and_then(|v| v.as_str())
+}
+
+// Slash command options can't have new lines, so rows come in separated by spaces
+fn spaces_to_lines(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join("\n")
+}
+
+fn command_reply(
+    store: &dyn Store,
+    config: &Config,
+    command: &ApplicationCommandInteraction,
+) -> Result<Reply, BotError> {
+    let options = &command.data.options;
+    let guild_results = || {
+        let guild_id = command.guild_id.ok_or(BotError::NoGuild)?;
+        store.guild_results(guild_id.0).map_err(BotError::Store)
+    };
+    Ok(match command.data.name.as_str() {
+        "wordlepath" => Reply::Text(commands::wordle_path(
+            &config.answers,
+            option_str(options, "answer"),
+            &spaces_to_lines(option_str(options, "grid").unwrap_or_default()),
+            Utc::now().date_naive(),
+        )),
+        "stats" => {
+            let results = guild_results()?;
+            let user_id = option_str(options, "user")
+                .and_then(|id| id.parse().ok())
+                .unwrap_or(command.user.id.0);
+            Reply::Text(commands::stats(
+                &results,
+                user_id,
+                option_str(options, "game"),
+            ))
+        }
+        "leaderboard" => {
+            let results = guild_results()?;
+            let window = option_str(options, "window")
+                .and_then(Window::parse)
+                .unwrap_or(Window::All);
+            commands::leaderboard(&results, option_str(options, "game"), window)
+        }
+        name => Reply::Text(format!("Weird, I don't know the /{} command", name)),
+    })
+}
+
+pub async fn handle_command(
+    http: &Http,
+    store: &dyn Store,
+    config: &Config,
+    threads: &ThreadCache,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), BotError> {
+    if command.data.name == "wordlestats" {
+        return handle_wordle_stats(http, store, config, threads, command).await;
+    }
+    let reply = command_reply(store, config, command)?;
+    respond(http, command, reply, false).await
+}
+
+// Log what went wrong, and tell whoever ran the command if there's something
+// they can do about it. Only they get to see it.
+pub async fn report(
+    http: &Http,
+    command: &ApplicationCommandInteraction,
+    during: &str,
+    e: BotError,
+) {
+    e.log(during);
+    if let Some(text) = e.user_message() {
+        if let Err(e) = respond(http, command, Reply::Text(text), true).await {
+            e.log("report");
+        }
+    }
+}
+
+// Wordle stats list this puzzle's words, so by default only you get to see them.
+async fn handle_wordle_stats(
+    http: &Http,
+    store: &dyn Store,
+    config: &Config,
+    threads: &ThreadCache,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), BotError> {
+    let guesses = spaces_to_lines(option_str(&command.data.options, "guesses").unwrap_or_default());
+    let policy = command
+        .guild_id
+        .map(|guild_id| guild_settings(store, config, guild_id.0).spoiler_policy());
+    // Working the stats out (and finding the thread) can take longer than
+    // discord waits for an answer, so say one's coming first
+    let ephemeral = matches!(
+        policy,
+        Some(SpoilerPolicy::Thread) | Some(SpoilerPolicy::Private)
+    );
+    defer(http, command, ephemeral).await?;
+    let spoilers = policy == Some(SpoilerPolicy::Spoiler);
+    let stats =
+        commands::run_wordle_stats(&guesses, spoilers, &config.answers, config.words_to_show).await;
+    if let (Some(SpoilerPolicy::Thread), Some(guild_id)) = (policy, command.guild_id) {
+        let thread = latest_solvers_thread(
+            http,
+            threads,
+            store,
+            guild_id,
+            command.user.id.0,
+            commands::DEFAULT_GAME,
+        )
+        .await;
+        if let Some(thread) = thread {
+            let text = format!("{}\n{}", command.user.mention(), stats);
+            match http.post_message(thread.id, &text).await {
+                Ok(()) => {
+                    let posted = format!("Posted your stats in {}", thread.id.mention());
+                    return edit_reply(http, command, posted).await;
+                }
+                // They still get them, just not in the thread
+                Err(e) => e.log("stats thread"),
+            }
+        }
+    }
+    edit_reply(http, command, stats).await
+}
+
+// "Thinking..." until edit_reply fills it in
+async fn defer(
+    http: &Http,
+    command: &ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), BotError> {
+    command
+        .create_interaction_response(http, |r| {
+            r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    if ephemeral {
+                        d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    }
+                    d
+                })
+        })
+        .await?;
+    Ok(())
+}
+
+async fn edit_reply(
+    http: &Http,
+    command: &ApplicationCommandInteraction,
+    text: String,
+) -> Result<(), BotError> {
+    command
+        .edit_original_interaction_response(http, |r| r.content(text))
+        .await?;
+    Ok(())
+}
+
+// Ephemeral replies are only shown to whoever ran the command
+async fn respond(
+    http: &Http,
+    command: &ApplicationCommandInteraction,
+    reply: Reply,
+    ephemeral: bool,
+) -> Result<(), BotError> {
+    command
+        .create_interaction_response(http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    if ephemeral {
+                        d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    }
+                    match reply {
+                        Reply::Text(text) => d.content(text),
+                        Reply::Embed {
+                            title,
+                            description,
+                            footer,
+                        } => d.create_embed(|e| {
+                            e.title(title);
+                            e.description(description);
+                            e.footer(|f| f.text(footer));
+                            e
+                        }),
+                    }
+                })
+        })
+        .await?;
+    Ok(())
+}
+
+pub async fn handle_autocomplete(
+    http: &Http,
+    store: &dyn Store,
+    autocomplete: &AutocompleteInteraction,
+) -> Result<(), BotError> {
+    let typed = autocomplete
+        .data
+        .options
+        .iter()
+        .find(|o| o.focused)
+        .and_then(|o| o.value.as_ref())
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let results = match autocomplete.guild_id {
+        Some(guild_id) => store.guild_results(guild_id.0).unwrap_or_default(),
+        None => vec![],
+    };
+    let names = commands::game_names(&results, typed);
+    autocomplete
+        .create_autocomplete_response(http, |r| {
+            for name in names.iter().take(MAX_CHOICES) {
+                r.add_string_choice(name, name);
+            }
+            r
+        })
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{command_definitions, commands_up_to_date, spaces_to_lines};
+
+    #[test]
+    fn test_spaces_to_lines() {
+        assert_eq!(spaces_to_lines("train weigh  oxide"), "train\nweigh\noxide");
+        assert_eq!(
+            spaces_to_lines("⬛🟨🟨⬛⬛ 🟩🟩🟩🟩🟩"),
+            "⬛🟨🟨⬛⬛\n🟩🟩🟩🟩🟩"
+        );
+    }
+
+    #[test]
+    fn test_commands_up_to_date() {
+        let defined = command_definitions();
+        // What discord gives back, in its own order and with its own extras
+        let mut registered: Vec<_> = defined.iter().rev().cloned().collect();
+        for (id, command) in registered.iter_mut().enumerate() {
+            command["id"] = json!(id.to_string());
+            command["version"] = json!("1");
+        }
+        assert!(commands_up_to_date(&defined, &registered));
+
+        let mut changed = registered.clone();
+        changed[0]["options"][0]["description"] = json!("Something else");
+        assert!(!commands_up_to_date(&defined, &changed));
+        let mut changed = registered.clone();
+        changed[0]["options"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("autocomplete");
+        assert!(!commands_up_to_date(&defined, &changed));
+        assert!(!commands_up_to_date(&defined, &registered[1..]));
+        assert!(!commands_up_to_date(&defined, &[]));
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::games::game_or_default;
+use crate::store::{GameResult, Score};
+
+const MAX_GUESSES: usize = 6;
+const HISTOGRAM_WIDTH: u32 = 16;
+// How many games worth of the guild average to mix into everyone's average, so
+// someone with one lucky 2/6 doesn't top the board
+const PRIOR_GAMES: f64 = 3.0;
+
+// Everything the wordle stats screen shows, for one user and one game.
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub played: u32,
+    pub won: u32,
+    pub failed: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    // Guesses across every win, for the mean
+    pub total_guesses: u32,
+    // How many games were won in 1 through 6 guesses. Games scored by total
+    // guesses over a few boards (Quordle) don't fit in here.
+    pub distribution: [u32; MAX_GUESSES],
+}
+
+impl Stats {
+    // Out of the games that had a score, how many were won
+    pub fn win_rate(&self) -> Option<f64> {
+        let scored = self.won + self.failed;
+        if scored == 0 {
+            return None;
+        }
+        Some(self.won as f64 / scored as f64)
+    }
+
+    pub fn mean_guesses(&self) -> Option<f64> {
+        if self.won == 0 {
+            return None;
+        }
+        Some(self.total_guesses as f64 / self.won as f64)
+    }
+}
+
+// Work out the stats for a user's results in a single game. Streaks go by day
+// number: a streak is a run of consecutive days without a fail, and it's only
+// current if it reaches `latest_day` (the newest day anyone has posted) or the
+// day before, since they might just not have played today yet.
+pub fn calculate_stats<'a>(
+    results: impl IntoIterator<Item = &'a GameResult>,
+    latest_day: u32,
+) -> Stats {
+    let mut stats = Stats::default();
+    let mut days = BTreeMap::new();
+    for result in results {
+        stats.played += 1;
+        match result.score {
+            Score::Solved(guesses) => {
+                stats.won += 1;
+                stats.total_guesses += guesses;
+                if (1..=MAX_GUESSES).contains(&(guesses as usize)) {
+                    stats.distribution[guesses as usize - 1] += 1;
+                }
+            }
+            Score::Failed => stats.failed += 1,
+            Score::Unscored => {}
+        }
+        days.insert(result.day, result.score != Score::Failed);
+    }
+
+    let mut streak = 0;
+    let mut last_day = None;
+    for (day, kept) in days.iter() {
+        if !kept {
+            streak = 0;
+        } else if last_day.map(|last| last + 1) == Some(*day) {
+            streak += 1;
+        } else {
+            streak = 1;
+        }
+        stats.max_streak = stats.max_streak.max(streak);
+        last_day = Some(*day);
+    }
+    if let Some(last_day) = last_day {
+        if last_day + 1 >= latest_day {
+            stats.current_streak = streak;
+        }
+    }
+    stats
+}
+
+pub fn format_stats(stats: &Stats) -> String {
+    let mut msg = format!("Played: {}", stats.played);
+    if let Some(win_rate) = stats.win_rate() {
+        msg.push_str(&format!(" | Win %: {:.0}", win_rate * 100.0));
+    }
+    msg.push_str(&format!(
+        " | Current streak: {} | Max streak: {}",
+        stats.current_streak, stats.max_streak
+    ));
+    if let Some(mean) = stats.mean_guesses() {
+        msg.push_str(&format!(" | Mean guesses: {:.2}", mean));
+    }
+    if stats.won + stats.failed == 0 || stats.distribution.iter().sum::<u32>() != stats.won {
+        return msg;
+    }
+
+    let most = stats
+        .distribution
+        .iter()
+        .copied()
+        .chain(std::iter::once(stats.failed))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bar = |count: u32| "█".repeat((count * HISTOGRAM_WIDTH).div_ceil(most) as usize);
+    msg.push_str("\n```\n");
+    for (i, count) in stats.distribution.iter().enumerate() {
+        msg.push_str(&format!("{} {} {}\n", i + 1, bar(*count), count));
+    }
+    msg.push_str(&format!("X {} {}\n```", bar(stats.failed), stats.failed));
+    msg
+}
+
+// A one line summary for just the hard mode games, streaks don't mean much when
+// you can flip it on and off
+pub fn format_hard_mode(stats: &Stats) -> String {
+    let mut msg = format!("Hard mode: Played: {}", stats.played);
+    if let Some(win_rate) = stats.win_rate() {
+        msg.push_str(&format!(" | Win %: {:.0}", win_rate * 100.0));
+    }
+    if let Some(mean) = stats.mean_guesses() {
+        msg.push_str(&format!(" | Mean guesses: {:.2}", mean));
+    }
+    msg
+}
+
+// A score as a number of guesses for averaging, None if there's no score
+pub fn score_value(result: &GameResult) -> Option<f64> {
+    match result.score {
+        Score::Solved(guesses) => Some(guesses as f64),
+        Score::Failed => Some(game_or_default(&result.game).failed_score()),
+        Score::Unscored => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    Week,
+    Month,
+    All,
+}
+
+impl Window {
+    pub fn parse(arg: &str) -> Option<Window> {
+        match arg.to_lowercase().as_str() {
+            "week" => Some(Window::Week),
+            "month" => Some(Window::Month),
+            "all" => Some(Window::All),
+            _ => None,
+        }
+    }
+
+    // Windows go by day number back from the newest day posted
+    fn days(&self) -> Option<u32> {
+        match self {
+            Window::Week => Some(7),
+            Window::Month => Some(30),
+            Window::All => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Window::Week => "this week",
+            Window::Month => "this month",
+            Window::All => "all time",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub user_id: u64,
+    pub played: u32,
+    pub won: u32,
+    pub failed: u32,
+    // Plain average score, fails counting as 7
+    pub average: Option<f64>,
+    // The average pulled towards the guild average, what everyone gets ranked on
+    pub rating: Option<f64>,
+    // Out of the days anyone in the guild played, how many this user did
+    pub participation: f64,
+}
+
+impl LeaderboardEntry {
+    pub fn win_rate(&self) -> Option<f64> {
+        let scored = self.won + self.failed;
+        if scored == 0 {
+            return None;
+        }
+        Some(self.won as f64 / scored as f64)
+    }
+}
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// One line per person, for the top of the leaderboard.
+pub fn format_leaderboard(entries: &[LeaderboardEntry]) -> String {
+    entries
+        .iter()
+        .take(LEADERBOARD_SIZE)
+        .enumerate()
+        .map(|(i, entry)| {
+            let mut line = format!("**{}.** <@{}>", i + 1, entry.user_id);
+            if let (Some(rating), Some(average)) = (entry.rating, entry.average) {
+                line.push_str(&format!(" {:.2} (avg {:.2})", rating, average));
+            }
+            if let Some(win_rate) = entry.win_rate() {
+                line.push_str(&format!(" · {:.0}% wins", win_rate * 100.0));
+            }
+            line.push_str(&format!(
+                " · {} played ({:.0}%)",
+                entry.played,
+                entry.participation * 100.0
+            ));
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Rank everyone who played a game in the window, best first. Scored games go by
+// a bayesian average of guesses (lower is better) then win rate, games without a
+// score go by how often they were played.
+pub fn calculate_leaderboard<'a>(
+    results: impl IntoIterator<Item = &'a GameResult>,
+    window: Window,
+) -> Vec<LeaderboardEntry> {
+    let results: Vec<_> = results.into_iter().collect();
+    let latest_day = match results.iter().map(|r| r.day).max() {
+        Some(day) => day,
+        None => return vec![],
+    };
+    let first_day = window
+        .days()
+        .map_or(0, |days| (latest_day + 1).saturating_sub(days));
+    let results: Vec<_> = results.into_iter().filter(|r| r.day >= first_day).collect();
+
+    let days_played: BTreeSet<_> = results.iter().map(|r| r.day).collect();
+    let mut by_user: HashMap<u64, Vec<&GameResult>> = HashMap::new();
+    for result in results.iter() {
+        by_user.entry(result.user_id).or_default().push(result);
+    }
+
+    let all_scores: Vec<f64> = results.iter().filter_map(|r| score_value(r)).collect();
+    let guild_average = all_scores.iter().sum::<f64>() / all_scores.len().max(1) as f64;
+
+    let mut entries: Vec<_> = by_user
+        .into_iter()
+        .map(|(user_id, results)| {
+            let stats = calculate_stats(results.iter().copied(), latest_day);
+            let scores: Vec<f64> = results.iter().filter_map(|r| score_value(r)).collect();
+            let total: f64 = scores.iter().sum();
+            let n = scores.len() as f64;
+            let (average, rating) = if scores.is_empty() {
+                (None, None)
+            } else {
+                (
+                    Some(total / n),
+                    Some((total + PRIOR_GAMES * guild_average) / (n + PRIOR_GAMES)),
+                )
+            };
+            LeaderboardEntry {
+                user_id,
+                played: stats.played,
+                won: stats.won,
+                failed: stats.failed,
+                average,
+                rating,
+                participation: stats.played as f64 / days_played.len() as f64,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        let rating = |e: &LeaderboardEntry| e.rating.unwrap_or(f64::MAX);
+        rating(a)
+            .partial_cmp(&rating(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(
+                b.win_rate()
+                    .partial_cmp(&a.win_rate())
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+            .then(b.played.cmp(&a.played))
+            .then(a.user_id.cmp(&b.user_id))
+    });
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        calculate_leaderboard, calculate_stats, format_hard_mode, format_leaderboard, format_stats,
+        Stats, Window,
+    };
+    use crate::store::{test_result, GameResult, Score};
+
+    fn result(day: u32, score: Score) -> GameResult {
+        user_result(3, day, score)
+    }
+
+    fn user_result(user_id: u64, day: u32, score: Score) -> GameResult {
+        test_result(user_id, "Wordle", day, score)
+    }
+
+    #[test]
+    fn test_calculate_stats() {
+        let results = vec![
+            result(1, Score::Solved(3)),
+            result(2, Score::Solved(4)),
+            result(3, Score::Solved(4)),
+            result(4, Score::Failed),
+            result(5, Score::Solved(2)),
+            // skipped a day
+            result(7, Score::Solved(6)),
+            result(8, Score::Solved(3)),
+        ];
+        let stats = calculate_stats(&results, 8);
+        assert_eq!(
+            stats,
+            Stats {
+                played: 7,
+                won: 6,
+                failed: 1,
+                current_streak: 2,
+                max_streak: 3,
+                total_guesses: 22,
+                distribution: [0, 1, 2, 2, 0, 1],
+            }
+        );
+        assert_eq!(stats.mean_guesses(), Some(22.0 / 6.0));
+        assert_eq!(stats.win_rate(), Some(6.0 / 7.0));
+
+        // Haven't played today yet, the streak is still going
+        assert_eq!(calculate_stats(&results, 9).current_streak, 2);
+        // Missed yesterday too, it's over
+        assert_eq!(calculate_stats(&results, 10).current_streak, 0);
+    }
+
+    #[test]
+    fn test_calculate_stats_order_and_fails() {
+        // Results don't have to come in day order
+        let results = vec![
+            result(12, Score::Solved(5)),
+            result(10, Score::Solved(5)),
+            result(11, Score::Solved(5)),
+        ];
+        let stats = calculate_stats(&results, 12);
+        assert_eq!((stats.current_streak, stats.max_streak), (3, 3));
+
+        let results = vec![result(10, Score::Solved(5)), result(11, Score::Failed)];
+        let stats = calculate_stats(&results, 11);
+        assert_eq!((stats.current_streak, stats.max_streak), (0, 1));
+
+        let stats = calculate_stats(&[], 11);
+        assert_eq!(stats, Stats::default());
+        assert_eq!(stats.win_rate(), None);
+        assert_eq!(stats.mean_guesses(), None);
+    }
+
+    #[test]
+    fn test_unscored_stats() {
+        let results = vec![result(1, Score::Unscored), result(2, Score::Unscored)];
+        let stats = calculate_stats(&results, 2);
+        assert_eq!((stats.played, stats.current_streak), (2, 2));
+        assert_eq!(stats.win_rate(), None);
+        assert_eq!(
+            format_stats(&stats),
+            "Played: 2 | Current streak: 2 | Max streak: 2"
+        );
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let results = vec![
+            result(1, Score::Solved(3)),
+            result(2, Score::Solved(3)),
+            result(3, Score::Solved(4)),
+            result(4, Score::Failed),
+        ];
+        assert_eq!(
+            format_stats(&calculate_stats(&results, 4)),
+            "Played: 4 | Win %: 75 | Current streak: 0 | Max streak: 3 | Mean guesses: 3.33
+```
+1  0
+2  0
+3 ████████████████ 2
+4 ████████ 1
+5  0
+6  0
+X ████████ 1
+```"
+        );
+    }
+
+    #[test]
+    fn test_format_stats_totals() {
+        // Quordle scores are totals, too big for the histogram
+        let mut results = vec![result(1, Score::Solved(22)), result(2, Score::Solved(25))];
+        for result in results.iter_mut() {
+            result.game = "Quordle".to_string();
+        }
+        assert_eq!(
+            format_stats(&calculate_stats(&results, 2)),
+            "Played: 2 | Win %: 100 | Current streak: 2 | Max streak: 2 | Mean guesses: 23.50"
+        );
+    }
+
+    #[test]
+    fn test_format_hard_mode() {
+        let results = vec![
+            result(1, Score::Solved(3)),
+            result(2, Score::Solved(4)),
+            result(3, Score::Failed),
+        ];
+        assert_eq!(
+            format_hard_mode(&calculate_stats(&results, 3)),
+            "Hard mode: Played: 3 | Win %: 67 | Mean guesses: 3.50"
+        );
+    }
+
+    #[test]
+    fn test_window_parse() {
+        assert_eq!(Window::parse("week"), Some(Window::Week));
+        assert_eq!(Window::parse("Month"), Some(Window::Month));
+        assert_eq!(Window::parse("all"), Some(Window::All));
+        assert_eq!(Window::parse("Wordle"), None);
+    }
+
+    #[test]
+    fn test_calculate_leaderboard() {
+        let mut results = vec![
+            // One lucky game shouldn't beat a long run of good ones
+            user_result(1, 20, Score::Solved(2)),
+            user_result(3, 20, Score::Solved(6)),
+            user_result(3, 19, Score::Failed),
+        ];
+        for day in 10..20 {
+            results.push(user_result(2, day, Score::Solved(2 + day % 2)));
+        }
+        let board = calculate_leaderboard(&results, Window::All);
+        let order: Vec<_> = board.iter().map(|e| e.user_id).collect();
+        assert_eq!(order, vec![2, 1, 3]);
+        assert_eq!(board[0].average, Some(2.5));
+        assert_eq!(board[1].average, Some(2.0));
+        assert_eq!(board[2].win_rate(), Some(0.5));
+        assert_eq!(board[2].average, Some(6.5));
+        assert_eq!(board[0].participation, 10.0 / 11.0);
+
+        // Only the last week counts, so user 2 drops to 6 games
+        let board = calculate_leaderboard(&results, Window::Week);
+        assert_eq!(board[0].user_id, 2);
+        assert_eq!(board[0].played, 6);
+
+        assert!(calculate_leaderboard(&[], Window::All).is_empty());
+    }
+
+    #[test]
+    fn test_format_leaderboard() {
+        let results = vec![
+            user_result(1, 1, Score::Solved(3)),
+            user_result(1, 2, Score::Solved(4)),
+            user_result(2, 2, Score::Failed),
+        ];
+        assert_eq!(
+            format_leaderboard(&calculate_leaderboard(&results, Window::All)),
+            "**1.** <@1> 4.20 (avg 3.50) · 100% wins · 2 played (100%)
+**2.** <@2> 5.25 (avg 7.00) · 0% wins · 1 played (50%)"
+        );
+        let results = vec![user_result(1, 1, Score::Unscored)];
+        assert_eq!(
+            format_leaderboard(&calculate_leaderboard(&results, Window::All)),
+            "**1.** <@1> · 1 played (100%)"
+        );
+    }
+
+    #[test]
+    fn test_unscored_leaderboard() {
+        let results = vec![
+            user_result(1, 1, Score::Unscored),
+            user_result(2, 1, Score::Unscored),
+            user_result(2, 2, Score::Unscored),
+        ];
+        let board = calculate_leaderboard(&results, Window::All);
+        let order: Vec<_> = board.iter().map(|e| (e.user_id, e.participation)).collect();
+        assert_eq!(order, vec![(2, 1.0), (1, 0.5)]);
+    }
+}
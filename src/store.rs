@@ -0,0 +1,511 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::GuildSettings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Score {
+    // Got it in this many guesses
+    Solved(u32),
+    // The dreaded X/6
+    Failed,
+    // Games like Heardle and Quordle that don't share a single score
+    Unscored,
+}
+
+impl Score {
+    pub fn parse(result: &str) -> Score {
+        match result {
+            "X" => Score::Failed,
+            _ => match result.parse() {
+                Ok(guesses) => Score::Solved(guesses),
+                Err(_) => Score::Unscored,
+            },
+        }
+    }
+
+    // The inverse of parse, how the score shows up in a share ("3", "X" or "")
+    pub fn to_result(self) -> String {
+        match self {
+            Score::Solved(guesses) => guesses.to_string(),
+            Score::Failed => "X".to_string(),
+            Score::Unscored => String::new(),
+        }
+    }
+}
+
+// A single result someone posted in a guild.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub user_id: u64,
+    pub game: String,
+    pub day: u32,
+    pub score: Score,
+    pub hard_mode: bool,
+    pub grid: String,
+    // Unix seconds of when the message was posted
+    pub posted_at: i64,
+}
+
+impl GameResult {
+    // Someone reposting the same game and day only counts once
+    fn key(&self) -> (u64, u64, String, u32) {
+        (self.guild_id, self.user_id, self.game.clone(), self.day)
+    }
+}
+
+pub trait Store: Send + Sync {
+    // Save a result. Returns false (and saves nothing) if the user already
+    // posted a result for this game and day in the guild.
+    fn record_result(&self, result: &GameResult) -> anyhow::Result<bool>;
+
+    // Every result recorded in the guild, oldest first.
+    fn guild_results(&self, guild_id: u64) -> anyhow::Result<Vec<GameResult>>;
+
+    // What the guild's changed with `!wordlebot config`, nothing set if they
+    // haven't.
+    fn guild_settings(&self, guild_id: u64) -> anyhow::Result<GuildSettings>;
+
+    fn save_guild_settings(&self, guild_id: u64, settings: &GuildSettings) -> anyhow::Result<()>;
+}
+
+// Open the store described by `location`, either `sqlite:<path>` for an
+// embedded database or `file:<path>` for an append only file of json lines.
+pub fn open_store(location: &str) -> anyhow::Result<Box<dyn Store>> {
+    match location.split_once(':') {
+        Some(("sqlite", path)) => Ok(Box::new(SqliteStore::open(path)?)),
+        Some(("file", path)) => Ok(Box::new(FileStore::open(path)?)),
+        _ => Err(anyhow::anyhow!(
+            "store needs to be sqlite:<path> or file:<path>, got {}",
+            location
+        )),
+    }
+}
+
+struct FileStoreInner {
+    file: File,
+    results: Vec<GameResult>,
+    keys: HashSet<(u64, u64, String, u32)>,
+    settings_file: File,
+    settings: HashMap<u64, GuildSettings>,
+}
+
+// A line of the settings file, the last one for a guild wins
+#[derive(Serialize, Deserialize)]
+struct SettingsLine {
+    guild_id: u64,
+    settings: GuildSettings,
+}
+
+// Keeps every result in memory, and appends each new one to the file as a line
+// of json so it can be replayed on startup. Guild settings go the same way in
+// a `.settings.jsonl` file next to it.
+pub struct FileStore {
+    path: PathBuf,
+    settings_path: PathBuf,
+    inner: Mutex<FileStoreInner>,
+}
+
+fn open_append(path: &Path) -> anyhow::Result<File> {
+    Ok(OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?)
+}
+
+// Every line of json in the file. A line that doesn't parse gets skipped, or
+// cut off if it's the last one, which is what's left when the bot goes down
+// halfway through writing it. Otherwise the next line would be tacked onto it.
+fn read_lines<T: for<'de> Deserialize<'de>>(
+    mut file: &File,
+    path: &Path,
+) -> anyhow::Result<Vec<T>> {
+    let mut contents = vec![];
+    file.read_to_end(&mut contents)?;
+    let mut lines = vec![];
+    let mut start = 0;
+    for line in contents.split_inclusive(|b| *b == b'\n') {
+        let end = start + line.len();
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            match serde_json::from_slice(line) {
+                Ok(value) => lines.push(value),
+                Err(e) if end == contents.len() => {
+                    println!(
+                        "Cutting off a half written line at the end of {}: {}",
+                        path.display(),
+                        e
+                    );
+                    file.set_len(start as u64)?;
+                    return Ok(lines);
+                }
+                Err(e) => println!("Skipping a bad line in {}: {}", path.display(), e),
+            }
+        }
+        start = end;
+    }
+    if contents.last().is_some_and(|b| *b != b'\n') {
+        file.write_all(b"\n")?;
+    }
+    Ok(lines)
+}
+
+impl FileStore {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<FileStore> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_append(&path)?;
+        let mut results = vec![];
+        let mut keys = HashSet::new();
+        for result in read_lines::<GameResult>(&file, &path)? {
+            if keys.insert(result.key()) {
+                results.push(result);
+            }
+        }
+        let settings_path = path.with_extension("settings.jsonl");
+        let settings_file = open_append(&settings_path)?;
+        let settings = read_lines::<SettingsLine>(&settings_file, &settings_path)?
+            .into_iter()
+            .map(|line| (line.guild_id, line.settings))
+            .collect();
+        Ok(FileStore {
+            path,
+            settings_path,
+            inner: Mutex::new(FileStoreInner {
+                file,
+                results,
+                keys,
+                settings_file,
+                settings,
+            }),
+        })
+    }
+}
+
+impl Store for FileStore {
+    fn record_result(&self, result: &GameResult) -> anyhow::Result<bool> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.keys.contains(&result.key()) {
+            return Ok(false);
+        }
+        let mut line = serde_json::to_string(result)?;
+        line.push('\n');
+        inner
+            .file
+            .write_all(line.as_bytes())
+            .map_err(|e| anyhow::anyhow!("couldn't write to {}: {}", self.path.display(), e))?;
+        inner.keys.insert(result.key());
+        inner.results.push(result.clone());
+        Ok(true)
+    }
+
+    fn guild_results(&self, guild_id: u64) -> anyhow::Result<Vec<GameResult>> {
+        let inner = self.inner.lock().unwrap();
+        Ok(inner
+            .results
+            .iter()
+            .filter(|r| r.guild_id == guild_id)
+            .cloned()
+            .collect())
+    }
+
+    fn guild_settings(&self, guild_id: u64) -> anyhow::Result<GuildSettings> {
+        let inner = self.inner.lock().unwrap();
+        Ok(inner.settings.get(&guild_id).cloned().unwrap_or_default())
+    }
+
+    fn save_guild_settings(&self, guild_id: u64, settings: &GuildSettings) -> anyhow::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut line = serde_json::to_string(&SettingsLine {
+            guild_id,
+            settings: settings.clone(),
+        })?;
+        line.push('\n');
+        inner
+            .settings_file
+            .write_all(line.as_bytes())
+            .map_err(|e| {
+                anyhow::anyhow!("couldn't write to {}: {}", self.settings_path.display(), e)
+            })?;
+        inner.settings.insert(guild_id, settings.clone());
+        Ok(())
+    }
+}
+
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<SqliteStore> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS results (
+                id INTEGER PRIMARY KEY,
+                guild_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                game TEXT NOT NULL,
+                day INTEGER NOT NULL,
+                score TEXT NOT NULL,
+                hard_mode INTEGER NOT NULL,
+                grid TEXT NOT NULL,
+                posted_at INTEGER NOT NULL,
+                UNIQUE (guild_id, user_id, game, day)
+            );
+            CREATE TABLE IF NOT EXISTS guild_settings (
+                guild_id INTEGER PRIMARY KEY,
+                settings TEXT NOT NULL
+            );",
+        )?;
+        Ok(SqliteStore {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl Store for SqliteStore {
+    fn record_result(&self, result: &GameResult) -> anyhow::Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO results
+                (guild_id, channel_id, user_id, game, day, score, hard_mode, grid, posted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                result.guild_id as i64,
+                result.channel_id as i64,
+                result.user_id as i64,
+                result.game,
+                result.day,
+                result.score.to_result(),
+                result.hard_mode,
+                result.grid,
+                result.posted_at,
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    fn guild_results(&self, guild_id: u64) -> anyhow::Result<Vec<GameResult>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT guild_id, channel_id, user_id, game, day, score, hard_mode, grid, posted_at
+             FROM results WHERE guild_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![guild_id as i64], |row| {
+            Ok(GameResult {
+                guild_id: row.get::<_, i64>(0)? as u64,
+                channel_id: row.get::<_, i64>(1)? as u64,
+                user_id: row.get::<_, i64>(2)? as u64,
+                game: row.get(3)?,
+                day: row.get(4)?,
+                score: Score::parse(&row.get::<_, String>(5)?),
+                hard_mode: row.get(6)?,
+                grid: row.get(7)?,
+                posted_at: row.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn guild_settings(&self, guild_id: u64) -> anyhow::Result<GuildSettings> {
+        let conn = self.conn.lock().unwrap();
+        let settings: Option<String> = conn
+            .query_row(
+                "SELECT settings FROM guild_settings WHERE guild_id = ?1",
+                params![guild_id as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match settings {
+            Some(settings) => Ok(serde_json::from_str(&settings)?),
+            None => Ok(GuildSettings::default()),
+        }
+    }
+
+    fn save_guild_settings(&self, guild_id: u64, settings: &GuildSettings) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO guild_settings (guild_id, settings) VALUES (?1, ?2)",
+            params![guild_id as i64, serde_json::to_string(settings)?],
+        )?;
+        Ok(())
+    }
+}
+
+// Someone's result in guild 1, for tests to start from
+#[cfg(test)]
+pub fn test_result(user_id: u64, game: &str, day: u32, score: Score) -> GameResult {
+    GameResult {
+        guild_id: 1,
+        channel_id: 2,
+        user_id,
+        game: game.to_string(),
+        day,
+        score,
+        hard_mode: false,
+        grid: String::new(),
+        posted_at: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open_store, test_result as result, FileStore, Score, Store};
+    use crate::settings::GuildSettings;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("wordlebot-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn check_store(store: &dyn Store) {
+        assert!(store
+            .record_result(&result(10, "Wordle", 229, Score::Solved(3)))
+            .unwrap());
+        assert!(store
+            .record_result(&result(10, "Wordle", 230, Score::Failed))
+            .unwrap());
+        assert!(store
+            .record_result(&result(11, "Wordle", 229, Score::Solved(6)))
+            .unwrap());
+        assert!(store
+            .record_result(&result(10, "Heardle", 16, Score::Unscored))
+            .unwrap());
+        // Reposting the same day doesn't count twice, even with a different score
+        assert!(!store
+            .record_result(&result(10, "Wordle", 229, Score::Solved(1)))
+            .unwrap());
+
+        let mut other_guild = result(10, "Wordle", 229, Score::Solved(2));
+        other_guild.guild_id = 3;
+        assert!(store.record_result(&other_guild).unwrap());
+
+        let results = store.guild_results(1).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                result(10, "Wordle", 229, Score::Solved(3)),
+                result(10, "Wordle", 230, Score::Failed),
+                result(11, "Wordle", 229, Score::Solved(6)),
+                result(10, "Heardle", 16, Score::Unscored),
+            ]
+        );
+        assert_eq!(store.guild_results(3).unwrap(), vec![other_guild]);
+        assert!(store.guild_results(4).unwrap().is_empty());
+
+        assert_eq!(store.guild_settings(1).unwrap(), GuildSettings::default());
+        store.save_guild_settings(1, &settings("prefix ?")).unwrap();
+        store
+            .save_guild_settings(1, &settings("games wordle"))
+            .unwrap();
+        store.save_guild_settings(3, &settings("prefix ?")).unwrap();
+        assert_eq!(store.guild_settings(1).unwrap(), settings("games wordle"));
+        assert_eq!(store.guild_settings(3).unwrap(), settings("prefix ?"));
+    }
+
+    fn settings(change: &str) -> GuildSettings {
+        let mut settings = GuildSettings::default();
+        settings.apply(change).unwrap();
+        settings
+    }
+
+    #[test]
+    fn test_score_parse() {
+        for (result, score) in [
+            ("3", Score::Solved(3)),
+            ("X", Score::Failed),
+            ("", Score::Unscored),
+        ]
+        .iter()
+        {
+            assert_eq!(Score::parse(result), *score);
+            assert_eq!(score.to_result(), *result);
+        }
+    }
+
+    #[test]
+    fn test_file_store() {
+        let path = temp_path("results.jsonl");
+        let settings_path = temp_path("results.settings.jsonl");
+        check_store(&FileStore::open(&path).unwrap());
+
+        // Everything comes back after a restart, dedupe included
+        let store = FileStore::open(&path).unwrap();
+        assert_eq!(store.guild_results(1).unwrap().len(), 4);
+        assert!(!store
+            .record_result(&result(11, "Wordle", 229, Score::Solved(6)))
+            .unwrap());
+        assert_eq!(store.guild_settings(1).unwrap().prefix, None);
+        assert!(!store.guild_settings(1).unwrap().game_enabled("Heardle"));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&settings_path).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_bad_lines() {
+        let path = temp_path("bad.jsonl");
+        let settings_path = temp_path("bad.settings.jsonl");
+        let line = |score| serde_json::to_string(&result(10, "Wordle", 229, score)).unwrap();
+        let mut other_day = result(11, "Wordle", 229, Score::Solved(4));
+        other_day.day = 230;
+        let written = format!(
+            "{}\nnot json\n{}\n{}",
+            line(Score::Solved(3)),
+            serde_json::to_string(&other_day).unwrap(),
+            // The bot went down halfway through writing this one
+            &line(Score::Solved(5))[..40],
+        );
+        std::fs::write(&path, &written).unwrap();
+
+        let store = FileStore::open(&path).unwrap();
+        assert_eq!(
+            store.guild_results(1).unwrap(),
+            vec![result(10, "Wordle", 229, Score::Solved(3)), other_day]
+        );
+        assert!(store
+            .record_result(&result(12, "Wordle", 229, Score::Solved(2)))
+            .unwrap());
+        assert_eq!(
+            FileStore::open(&path)
+                .unwrap()
+                .guild_results(1)
+                .unwrap()
+                .len(),
+            3
+        );
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&settings_path).unwrap();
+    }
+
+    #[test]
+    fn test_sqlite_store() {
+        check_store(open_store("sqlite::memory:").unwrap().as_ref());
+
+        let path = temp_path("results.db");
+        let location = format!("sqlite:{}", path.display());
+        check_store(open_store(&location).unwrap().as_ref());
+        let store = open_store(&location).unwrap();
+        assert_eq!(store.guild_results(1).unwrap().len(), 4);
+        assert!(!store
+            .record_result(&result(11, "Wordle", 229, Score::Solved(6)))
+            .unwrap());
+        assert!(!store.guild_settings(1).unwrap().game_enabled("Heardle"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_store() {
+        assert!(open_store("wordlebot.db").is_err());
+        assert!(open_store("postgres:wordlebot").is_err());
+    }
+}
@@ -0,0 +1,1123 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::{
+    model::{
+        channel::ChannelType,
+        guild::PremiumTier,
+        id::{ChannelId, GuildId},
+        Permissions,
+    },
+    prelude::Mentionable,
+};
+
+use crate::cache::ThreadCache;
+use crate::error::BotError;
+use crate::games::Game;
+use crate::gate::GateMode;
+use crate::platform::{ChatPlatform, Thread};
+use crate::store::{GameResult, Store};
+
+// The pinned summary at the top of each solvers thread starts with this
+const SUMMARY_HEADER: &str = "Solvers so far";
+
+// How long a thread can go quiet before discord archives it, in minutes.
+// Discord only allows these four.
+pub fn parse_archive_duration(duration: &str) -> Option<u16> {
+    match duration.trim().to_lowercase().as_str() {
+        "1h" => Some(60),
+        "1d" => Some(1440),
+        "3d" => Some(4320),
+        "1w" => Some(10080),
+        _ => None,
+    }
+}
+
+// What kind of solvers threads a guild gets, when they aren't gated
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadMode {
+    // Private if the guild's boosted enough, public otherwise
+    Auto,
+    Private,
+    Public,
+}
+
+impl ThreadMode {
+    pub fn parse(mode: &str) -> Option<ThreadMode> {
+        match mode.trim().to_lowercase().as_str() {
+            "auto" => Some(ThreadMode::Auto),
+            "private" => Some(ThreadMode::Private),
+            "public" => Some(ThreadMode::Public),
+            _ => None,
+        }
+    }
+}
+
+pub fn solvers_thread_name(game: &str, day: u32) -> String {
+    format!("{} Solvers {}", game, day)
+}
+
+// The game and day back out of a solvers thread name
+pub fn parse_solvers_thread_name(name: &str) -> Option<(&str, u32)> {
+    let (game, day) = name.rsplit_once(" Solvers ")?;
+    Some((game, day.parse().ok()?))
+}
+
+pub async fn find_active_thread(
+    platform: &dyn ChatPlatform,
+    guild_id: GuildId,
+    name: &str,
+) -> Result<Option<Thread>, BotError> {
+    let threads = platform.active_threads(guild_id).await?;
+    Ok(threads.into_iter().find(|t| t.name == name))
+}
+
+// A thread that's gone quiet for long enough to be archived isn't in the
+// active ones, but late solvers (in other timezones, say) should still end up
+// there
+async fn find_archived_thread(
+    platform: &dyn ChatPlatform,
+    destination: &Destination,
+    name: &str,
+) -> Result<Option<Thread>, BotError> {
+    let (channel_id, kind) = match *destination {
+        Destination::Thread(channel_id, kind) => (channel_id, kind),
+        Destination::ForumPost(channel_id) => (channel_id, ChannelType::PublicThread),
+        Destination::Nowhere => return Ok(None),
+    };
+    let threads = platform.archived_threads(channel_id, kind).await?;
+    Ok(threads.into_iter().find(|t| t.name == name))
+}
+
+// Where a channel's solvers threads go, picked with `!wordlebot where`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ThreadStrategy {
+    // Private threads in the channel
+    Private,
+    // Public threads in the named channel, or the channel itself
+    Public(Option<String>),
+    // Posts in the named forum channel
+    Forum(String),
+    // No threads, results still get recorded
+    Off,
+}
+
+impl ThreadStrategy {
+    // `private`, `public [channel]`, `forum <channel>` or `none`
+    pub fn parse(strategy: &str) -> Option<ThreadStrategy> {
+        let strategy = strategy.trim();
+        let (kind, channel) = strategy
+            .split_once(char::is_whitespace)
+            .unwrap_or((strategy, ""));
+        let channel = channel.trim().trim_start_matches('#');
+        match (kind.to_lowercase().as_str(), channel) {
+            ("private", "") => Some(ThreadStrategy::Private),
+            ("public", "") => Some(ThreadStrategy::Public(None)),
+            ("public", channel) => Some(ThreadStrategy::Public(Some(channel.to_string()))),
+            ("forum", "") => None,
+            ("forum", channel) => Some(ThreadStrategy::Forum(channel.to_string())),
+            ("none", "") => Some(ThreadStrategy::Off),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<String> for ThreadStrategy {
+    type Error = String;
+
+    fn try_from(strategy: String) -> Result<ThreadStrategy, String> {
+        ThreadStrategy::parse(&strategy).ok_or_else(|| {
+            format!(
+                "expected private, public [channel], forum <channel> or none, got {}",
+                strategy
+            )
+        })
+    }
+}
+
+impl From<ThreadStrategy> for String {
+    fn from(strategy: ThreadStrategy) -> String {
+        match strategy {
+            ThreadStrategy::Private => "private".to_string(),
+            ThreadStrategy::Public(None) => "public".to_string(),
+            ThreadStrategy::Public(Some(channel)) => format!("public #{}", channel),
+            ThreadStrategy::Forum(channel) => format!("forum #{}", channel),
+            ThreadStrategy::Off => "none".to_string(),
+        }
+    }
+}
+
+// Where a result was posted, and how the guild wants its threads
+pub struct Placement {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    // None to go by the guild's boost level
+    pub strategy: Option<ThreadStrategy>,
+    // Where public threads go when going by the boost level, if it exists.
+    // {channel}_solvers unless the guild's picked another name.
+    pub solvers_channel: String,
+    pub gate: GateMode,
+    pub archive_duration: Option<u16>,
+}
+
+// Where a new solvers thread goes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Destination {
+    Thread(ChannelId, ChannelType),
+    ForumPost(ChannelId),
+    Nowhere,
+}
+
+// Where new solvers threads go for a placement, and why. Without a strategy
+// for the channel they go, in order of preference:
+// - private in the channel, if the guild's boosted enough
+// - public in the solvers channel
+// - public in the channel
+// Gating by membership always means private threads in the channel, and
+// threads gated by role that don't have another channel to hide in end up
+// private instead.
+pub async fn decide(
+    platform: &dyn ChatPlatform,
+    placement: &Placement,
+) -> Result<(Destination, String), BotError> {
+    let guild_id = placement.guild_id;
+    let here = placement.channel_id;
+    if placement.gate == GateMode::Members {
+        return Ok((
+            Destination::Thread(here, ChannelType::PrivateThread),
+            "the server keeps threads to that day's solvers (thread_gate members)".to_string(),
+        ));
+    }
+    let (destination, reason) = match &placement.strategy {
+        Some(ThreadStrategy::Off) => (
+            Destination::Nowhere,
+            "threads are turned off here".to_string(),
+        ),
+        Some(ThreadStrategy::Private) => (
+            Destination::Thread(here, ChannelType::PrivateThread),
+            "this channel's set to private threads".to_string(),
+        ),
+        Some(ThreadStrategy::Public(None)) => (
+            Destination::Thread(here, ChannelType::PublicThread),
+            "this channel's set to public threads".to_string(),
+        ),
+        Some(ThreadStrategy::Public(Some(name))) => {
+            match platform.find_channel(guild_id, name).await? {
+                Some(channel_id) => (
+                    Destination::Thread(channel_id, ChannelType::PublicThread),
+                    format!("this channel's set to public threads in #{}", name),
+                ),
+                None => (
+                    Destination::Thread(here, ChannelType::PublicThread),
+                    format!(
+                        "this channel's set to public threads in #{}, but there's no #{} channel",
+                        name, name
+                    ),
+                ),
+            }
+        }
+        Some(ThreadStrategy::Forum(name)) => match platform.find_channel(guild_id, name).await? {
+            Some(channel_id) => (
+                Destination::ForumPost(channel_id),
+                format!("this channel's set to posts in #{}", name),
+            ),
+            None => (
+                Destination::Thread(here, ChannelType::PublicThread),
+                format!(
+                    "this channel's set to posts in #{}, but there's no #{} channel",
+                    name, name
+                ),
+            ),
+        },
+        None => {
+            let boosted = placement.gate == GateMode::Open
+                && matches!(
+                    platform.premium_tier(guild_id).await?,
+                    PremiumTier::Tier3 | PremiumTier::Tier2
+                );
+            let solvers = &placement.solvers_channel;
+            if boosted {
+                (
+                    Destination::Thread(here, ChannelType::PrivateThread),
+                    "the server's boosted to level 2".to_string(),
+                )
+            } else if let Some(channel_id) = platform.find_channel(guild_id, solvers).await? {
+                (
+                    Destination::Thread(channel_id, ChannelType::PublicThread),
+                    format!("there's a #{} channel", solvers),
+                )
+            } else {
+                (
+                    Destination::Thread(here, ChannelType::PublicThread),
+                    format!("there's no #{} channel", solvers),
+                )
+            }
+        }
+    };
+    match destination {
+        Destination::Thread(channel_id, ChannelType::PublicThread)
+            if channel_id == here && placement.gate == GateMode::Role =>
+        {
+            Ok((
+                Destination::Thread(here, ChannelType::PrivateThread),
+                format!(
+                    "{}, and the server keeps threads to that day's solvers (thread_gate role) \
+                     so they can't be public",
+                    reason
+                ),
+            ))
+        }
+        _ => Ok((destination, reason)),
+    }
+}
+
+// Permissions the bot needs for threads to work at a destination, with how
+// discord names them
+fn needed_permissions(
+    destination: Destination,
+    gate: GateMode,
+) -> Vec<(Permissions, &'static str)> {
+    let mut needed = match destination {
+        Destination::Thread(_, ChannelType::PrivateThread) => {
+            vec![(
+                Permissions::CREATE_PRIVATE_THREADS,
+                "Create Private Threads",
+            )]
+        }
+        Destination::Thread(..) => {
+            vec![(Permissions::CREATE_PUBLIC_THREADS, "Create Public Threads")]
+        }
+        // Manage Channels is for adding the game's tag to the forum
+        Destination::ForumPost(_) => vec![
+            (Permissions::SEND_MESSAGES, "Send Messages"),
+            (Permissions::MANAGE_CHANNELS, "Manage Channels"),
+        ],
+        Destination::Nowhere => return vec![],
+    };
+    needed.extend([
+        (
+            Permissions::SEND_MESSAGES_IN_THREADS,
+            "Send Messages in Threads",
+        ),
+        (Permissions::MANAGE_MESSAGES, "Manage Messages"),
+        (Permissions::MANAGE_THREADS, "Manage Threads"),
+        // For finding the day's thread once it's archived
+        (Permissions::READ_MESSAGE_HISTORY, "Read Message History"),
+    ]);
+    if gate == GateMode::Role {
+        needed.push((Permissions::MANAGE_ROLES, "Manage Roles"));
+        if !matches!(destination, Destination::ForumPost(_)) {
+            needed.push((Permissions::MANAGE_CHANNELS, "Manage Channels"));
+        }
+    }
+    needed
+}
+
+// What `!wordlebot where` says, where threads for results posted in the
+// channel go and what's stopping them
+pub async fn explain_placement(
+    platform: &dyn ChatPlatform,
+    placement: &Placement,
+) -> Result<String, BotError> {
+    let (destination, reason) = decide(platform, placement).await?;
+    let (channel_id, place) = match destination {
+        Destination::Thread(channel_id, ChannelType::PrivateThread) => (
+            channel_id,
+            format!("private threads in {}", channel_id.mention()),
+        ),
+        Destination::Thread(channel_id, _) => (
+            channel_id,
+            format!("public threads in {}", channel_id.mention()),
+        ),
+        Destination::ForumPost(channel_id) => {
+            (channel_id, format!("posts in {}", channel_id.mention()))
+        }
+        Destination::Nowhere => {
+            return Ok(format!(
+                "Results posted here don't get solvers threads, since {}.",
+                reason
+            ))
+        }
+    };
+    let have = platform
+        .bot_permissions(placement.guild_id, channel_id)
+        .await?;
+    let missing: Vec<_> = needed_permissions(destination, placement.gate)
+        .into_iter()
+        .filter(|(permission, _)| !have.contains(*permission))
+        .map(|(_, name)| name)
+        .collect();
+    let permissions = match missing.as_slice() {
+        [] => "I've got all the permissions I need there.".to_string(),
+        missing => format!("I'm missing {} there.", missing.join(", ")),
+    };
+    Ok(format!(
+        "Results posted here get {}, since {}.\n{}",
+        place, reason, permissions
+    ))
+}
+
+// Where the day's thread turned up, or where a new one would go
+enum Lookup {
+    Found(Thread),
+    Missing(Destination),
+}
+
+// Look for the thread in the cache, then the guild's active threads, then
+// the archived ones where it would have been made, reopening it if it's been
+// archived or locked. Only call it holding the guild's lock.
+async fn lookup_thread(
+    platform: &dyn ChatPlatform,
+    cache: &ThreadCache,
+    placement: &Placement,
+    name: &str,
+) -> Result<Lookup, BotError> {
+    let guild_id = placement.guild_id;
+    let found = match cache.get(guild_id, name) {
+        Some(thread) => Some(thread),
+        // Only the first result of the day should get here, unless someone
+        // made the thread themselves
+        None => find_active_thread(platform, guild_id, name).await?,
+    };
+    let mut thread = match found {
+        Some(thread) => thread,
+        None => {
+            let destination = decide(platform, placement).await?.0;
+            match find_archived_thread(platform, &destination, name).await? {
+                Some(thread) => thread,
+                None => return Ok(Lookup::Missing(destination)),
+            }
+        }
+    };
+    // Someone a long way behind can turn up after it's been archived, or
+    // locked for being a day old
+    if thread.archived || thread.locked {
+        platform.reopen_thread(thread.id, thread.locked).await?;
+        thread.archived = false;
+        thread.locked = false;
+    }
+    cache.insert(thread.clone());
+    Ok(Lookup::Found(thread))
+}
+
+// The solvers thread called `name` if there is one, reopened if it's been
+// archived or locked
+pub async fn find_solvers_thread(
+    platform: &dyn ChatPlatform,
+    cache: &ThreadCache,
+    placement: &Placement,
+    name: &str,
+) -> Result<Option<Thread>, BotError> {
+    let _lock = cache.lock(placement.guild_id).await;
+    match lookup_thread(platform, cache, placement, name).await? {
+        Lookup::Found(thread) => Ok(Some(thread)),
+        Lookup::Missing(_) => Ok(None),
+    }
+}
+
+// Welcome someone to the solvers thread for their result, making it if
+// they're the first. Also says whether it's new, or gives back nothing if the
+// channel doesn't want threads.
+pub async fn welcome_solver(
+    platform: &dyn ChatPlatform,
+    cache: &ThreadCache,
+    placement: &Placement,
+    name: &str,
+    welcome: &str,
+) -> Result<Option<(Thread, bool)>, BotError> {
+    if placement.strategy == Some(ThreadStrategy::Off) {
+        return Ok(None);
+    }
+    // Held until the thread's made, so everyone else posting at the same time
+    // finds it rather than making their own
+    let lock = cache.lock(placement.guild_id).await;
+    let destination = match lookup_thread(platform, cache, placement, name).await? {
+        Lookup::Found(thread) => {
+            drop(lock);
+            platform.post_message(thread.id, welcome).await?;
+            return Ok(Some((thread, false)));
+        }
+        Lookup::Missing(destination) => destination,
+    };
+    let archive_duration = placement.archive_duration;
+    let thread = match destination {
+        Destination::Thread(channel_id, kind) => {
+            platform
+                .create_thread(channel_id, name, kind, archive_duration)
+                .await?
+        }
+        // The welcome is what starts the post, which gets tagged with the game
+        Destination::ForumPost(channel_id) => {
+            let game = parse_solvers_thread_name(name).map_or(name, |(game, _)| game);
+            let thread = platform
+                .create_forum_post(channel_id, name, welcome, game, archive_duration)
+                .await?;
+            cache.insert(thread.clone());
+            return Ok(Some((thread, true)));
+        }
+        Destination::Nowhere => return Ok(None),
+    };
+    cache.insert(thread.clone());
+    drop(lock);
+    platform.post_message(thread.id, welcome).await?;
+    Ok(Some((thread, true)))
+}
+
+// How long a puzzle's thread stays open. A new puzzle comes out in the first
+// timezone more than a day before the last one gets it, so anyone behind can
+// still be on yesterday's when today's thread goes up.
+const LOCK_AFTER_HOURS: i64 = 50;
+
+// Once everyone's moved on from a puzzle nobody should be posting in its
+// thread. Checked whenever a newer day's thread gets made.
+pub async fn lock_old_threads(
+    platform: &dyn ChatPlatform,
+    cache: &ThreadCache,
+    guild_id: GuildId,
+    game: &str,
+    day: u32,
+    now: DateTime<Utc>,
+) -> Result<(), BotError> {
+    for (thread_day, mut thread) in cache.game_threads(guild_id, game) {
+        let age = now - thread.id.created_at();
+        if thread_day >= day
+            || thread.locked
+            || thread.archived
+            || age < Duration::hours(LOCK_AFTER_HOURS)
+        {
+            continue;
+        }
+        platform.lock_thread(thread.id).await?;
+        thread.locked = true;
+        cache.insert(thread);
+    }
+    Ok(())
+}
+
+// Everyone who's posted the day's result so far, in the order they did
+fn solvers_summary(game: &dyn Game, day: u32, results: &[GameResult]) -> String {
+    let mut summary = format!("{} for {} {}:", SUMMARY_HEADER, game.name(), day);
+    for result in results
+        .iter()
+        .filter(|r| r.day == day && r.game.eq_ignore_ascii_case(game.name()))
+    {
+        summary.push_str(&format!(
+            "\n<@{}> {}",
+            result.user_id,
+            game.score_text(result.score)
+        ));
+        if result.hard_mode {
+            summary.push_str(" (hard mode)");
+        }
+    }
+    summary
+}
+
+// Keep the summary pinned in the thread up to date, posting and pinning it
+// the first time round.
+pub async fn update_summary(
+    platform: &dyn ChatPlatform,
+    store: &dyn Store,
+    thread: &Thread,
+    game: &dyn Game,
+    day: u32,
+) -> Result<(), BotError> {
+    let results = store
+        .guild_results(thread.guild_id.0)
+        .map_err(BotError::Store)?;
+    let summary = solvers_summary(game, day, &results);
+    let pinned = platform
+        .bot_pins(thread.id)
+        .await?
+        .into_iter()
+        .find(|(_, content)| content.starts_with(SUMMARY_HEADER));
+    match pinned {
+        Some((message_id, _)) => platform.edit_message(thread.id, message_id, &summary).await,
+        // Listing people shouldn't ping them all
+        None => platform.post_pinned_message(thread.id, &summary).await,
+    }
+}
+
+// The most recent day the user posted a result for the game
+fn latest_day(results: &[GameResult], user_id: u64, game: &str) -> Option<u32> {
+    results
+        .iter()
+        .filter(|r| r.user_id == user_id && r.game.eq_ignore_ascii_case(game))
+        .map(|r| r.day)
+        .max()
+}
+
+// The solvers thread for the last puzzle the user posted, as long as it's
+// still open for posting. Archived or locked ones don't count, whoever asked
+// gets their answer privately instead.
+pub async fn latest_solvers_thread(
+    platform: &dyn ChatPlatform,
+    cache: &ThreadCache,
+    store: &dyn Store,
+    guild_id: GuildId,
+    user_id: u64,
+    game: &str,
+) -> Option<Thread> {
+    let results = match store.guild_results(guild_id.0) {
+        Ok(results) => results,
+        Err(e) => {
+            dbg!(e);
+            return None;
+        }
+    };
+    let name = solvers_thread_name(game, latest_day(&results, user_id, game)?);
+    let thread = match cache.get(guild_id, &name) {
+        Some(thread) => thread,
+        None => match find_active_thread(platform, guild_id, &name).await {
+            Ok(thread) => thread?,
+            Err(e) => {
+                e.log("stats thread");
+                return None;
+            }
+        },
+    };
+    if thread.archived || thread.locked {
+        return None;
+    }
+    Some(thread)
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::{
+        channel::ChannelType,
+        guild::PremiumTier,
+        id::{ChannelId, GuildId},
+        Permissions,
+    };
+
+    use super::{
+        explain_placement, latest_day, latest_solvers_thread, lock_old_threads,
+        parse_archive_duration, parse_solvers_thread_name, solvers_summary, solvers_thread_name,
+        update_summary, welcome_solver, Placement, ThreadMode, ThreadStrategy,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use std::sync::atomic::Ordering;
+
+    use crate::cache::ThreadCache;
+    use crate::error::BotError;
+    use crate::games::find_game;
+    use crate::gate::GateMode;
+    use crate::platform::{fake::FakePlatform, Thread};
+    use crate::store::{open_store, test_result, GameResult, Score};
+
+    fn result(user_id: u64, game: &str, day: u32) -> GameResult {
+        test_result(user_id, game, day, Score::Solved(3))
+    }
+
+    #[test]
+    fn test_latest_day() {
+        let results = vec![
+            result(1, "Wordle", 229),
+            result(1, "Wordle", 231),
+            result(1, "Heardle", 300),
+            result(2, "Wordle", 232),
+        ];
+        assert_eq!(latest_day(&results, 1, "wordle"), Some(231));
+        assert_eq!(latest_day(&results, 3, "Wordle"), None);
+        assert_eq!(solvers_thread_name("Wordle", 231), "Wordle Solvers 231");
+        assert_eq!(
+            parse_solvers_thread_name("Wordle Solvers 231"),
+            Some(("Wordle", 231))
+        );
+        assert_eq!(parse_solvers_thread_name("Wordle Solvers"), None);
+        assert_eq!(parse_solvers_thread_name("general"), None);
+    }
+
+    #[test]
+    fn test_parse_archive_duration() {
+        assert_eq!(parse_archive_duration("1d"), Some(1440));
+        assert_eq!(parse_archive_duration("1W"), Some(10080));
+        assert_eq!(parse_archive_duration("2d"), None);
+    }
+
+    #[test]
+    fn test_solvers_summary() {
+        let mut hard = result(2, "Wordle", 231);
+        hard.hard_mode = true;
+        let results = vec![
+            result(1, "Wordle", 231),
+            result(1, "Wordle", 230),
+            result(3, "Heardle", 231),
+            hard,
+        ];
+        let wordle = find_game("Wordle").unwrap();
+        assert_eq!(
+            solvers_summary(wordle, 231, &results),
+            "Solvers so far for Wordle 231:\n<@1> 3/6\n<@2> 3/6 (hard mode)"
+        );
+        assert_eq!(
+            solvers_summary(wordle, 232, &results),
+            "Solvers so far for Wordle 232:"
+        );
+    }
+
+    const GENERAL: ChannelId = ChannelId(10);
+    const GENERAL_SOLVERS: ChannelId = ChannelId(11);
+
+    // A result posted in #general
+    fn placement(gate: GateMode, strategy: Option<ThreadStrategy>) -> Placement {
+        Placement {
+            guild_id: GuildId(1),
+            channel_id: GENERAL,
+            strategy,
+            solvers_channel: "general_solvers".to_string(),
+            gate,
+            archive_duration: None,
+        }
+    }
+
+    async fn welcome(
+        platform: &FakePlatform,
+        placement: &Placement,
+    ) -> Result<Option<(Thread, bool)>, BotError> {
+        welcome_solver(
+            platform,
+            &ThreadCache::default(),
+            placement,
+            "Wordle Solvers 231",
+            "Welcome!",
+        )
+        .await
+    }
+
+    // Gives back the thread's kind and channel
+    async fn placed(
+        tier: PremiumTier,
+        channels: &[(u64, &str)],
+        placement: Placement,
+    ) -> (ChannelType, Option<ChannelId>) {
+        let platform = FakePlatform::new(tier, channels);
+        let (thread, _) = welcome(&platform, &placement).await.unwrap().unwrap();
+        (thread.kind, thread.parent_id)
+    }
+
+    fn open() -> Placement {
+        placement(GateMode::Open, None)
+    }
+
+    #[tokio::test]
+    async fn test_welcome_solver() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let (thread, created) = welcome(&platform, &open()).await.unwrap().unwrap();
+        assert!(created);
+        assert_eq!(thread.name, "Wordle Solvers 231");
+        // The next person gets the same thread
+        let (again, created) = welcome(&platform, &open()).await.unwrap().unwrap();
+        assert!(!created);
+        assert_eq!(again, thread);
+        assert_eq!(platform.threads.lock().unwrap().len(), 1);
+        assert_eq!(
+            *platform.messages.lock().unwrap(),
+            vec![
+                (thread.id, "Welcome!".to_string()),
+                (thread.id, "Welcome!".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simultaneous_solvers() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let cache = ThreadCache::default();
+        let placement = open();
+        let (platform, cache, placement) = (&platform, &cache, &placement);
+        let welcome = move |welcome| {
+            welcome_solver(platform, cache, placement, "Wordle Solvers 231", welcome)
+        };
+        let (first, second) = tokio::join!(welcome("Welcome 1"), welcome("Welcome 2"));
+        let (first, first_created) = first.unwrap().unwrap();
+        let (second, second_created) = second.unwrap().unwrap();
+        assert_eq!(first, second);
+        assert!(first_created != second_created);
+        assert_eq!(platform.threads.lock().unwrap().len(), 1);
+
+        // Everyone after that gets it from the cache
+        let lookups = platform.lookups.load(Ordering::SeqCst);
+        welcome("Welcome 3").await.unwrap();
+        assert_eq!(platform.lookups.load(Ordering::SeqCst), lookups);
+        assert_eq!(platform.messages.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_archived_solvers() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let (thread, _) = welcome(&platform, &open()).await.unwrap().unwrap();
+        platform.threads.lock().unwrap()[0].archived = true;
+
+        // Someone a long way west turns up after it's been archived
+        let (again, created) = welcome(&platform, &open()).await.unwrap().unwrap();
+        assert!(!created);
+        assert_eq!(again, thread);
+        assert_eq!(platform.threads.lock().unwrap().len(), 1);
+        assert!(!platform.threads.lock().unwrap()[0].archived);
+
+        // Or the cache heard it was archived
+        let cache = ThreadCache::default();
+        let mut archived = thread.clone();
+        archived.archived = true;
+        cache.insert(archived);
+        platform.threads.lock().unwrap()[0].archived = true;
+        let (again, created) =
+            welcome_solver(&platform, &cache, &open(), "Wordle Solvers 231", "Welcome!")
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(!created);
+        assert_eq!(again, thread);
+        assert!(!platform.threads.lock().unwrap()[0].archived);
+        assert_eq!(
+            cache.get(GuildId(1), "Wordle Solvers 231"),
+            Some(thread.clone())
+        );
+
+        // It got locked before it was archived
+        {
+            let mut threads = platform.threads.lock().unwrap();
+            threads[0].archived = true;
+            threads[0].locked = true;
+        }
+        let (again, created) = welcome(&platform, &open()).await.unwrap().unwrap();
+        assert!(!created);
+        assert_eq!(again, thread);
+        assert!(!platform.threads.lock().unwrap()[0].archived);
+        assert!(!platform.threads.lock().unwrap()[0].locked);
+    }
+
+    #[tokio::test]
+    async fn test_update_summary() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let store = open_store("sqlite::memory:").unwrap();
+        let wordle = find_game("Wordle").unwrap();
+        let (thread, _) = welcome(&platform, &open()).await.unwrap().unwrap();
+        let summary = |content: &str| vec![(thread.id, content.to_string())];
+
+        store.record_result(&result(1, "Wordle", 231)).unwrap();
+        update_summary(&platform, store.as_ref(), &thread, wordle, 231)
+            .await
+            .unwrap();
+        assert_eq!(platform.pins.lock().unwrap().len(), 1);
+        assert_eq!(
+            platform.messages.lock().unwrap()[1..],
+            summary("Solvers so far for Wordle 231:\n<@1> 3/6")
+        );
+
+        // The pinned one gets edited from then on
+        store.record_result(&result(2, "Wordle", 231)).unwrap();
+        update_summary(&platform, store.as_ref(), &thread, wordle, 231)
+            .await
+            .unwrap();
+        assert_eq!(platform.pins.lock().unwrap().len(), 1);
+        assert_eq!(
+            platform.messages.lock().unwrap()[1..],
+            summary("Solvers so far for Wordle 231:\n<@1> 3/6\n<@2> 3/6")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latest_solvers_thread() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let store = open_store("sqlite::memory:").unwrap();
+        let cache = ThreadCache::default();
+        let latest =
+            || latest_solvers_thread(&platform, &cache, store.as_ref(), GuildId(1), 1, "Wordle");
+        store.record_result(&result(1, "Wordle", 231)).unwrap();
+        assert_eq!(latest().await, None);
+
+        let (thread, _) = welcome(&platform, &open()).await.unwrap().unwrap();
+        assert_eq!(latest().await, Some(thread.clone()));
+
+        // Nowhere to post once it's locked or archived
+        platform.threads.lock().unwrap()[0].locked = true;
+        assert_eq!(latest().await, None);
+        platform.threads.lock().unwrap()[0].locked = false;
+        platform.threads.lock().unwrap()[0].archived = true;
+        assert_eq!(latest().await, None);
+
+        // Same if the cache heard about it first
+        let mut locked = thread;
+        locked.locked = true;
+        platform.threads.lock().unwrap()[0].archived = false;
+        cache.insert(locked);
+        assert_eq!(latest().await, None);
+    }
+
+    // A thread made `hours` before noon on the 10th, which is when it's checked
+    fn made_at(hours: i64, name: &str) -> Thread {
+        let now = Utc.with_ymd_and_hms(2022, 4, 10, 12, 0, 0).unwrap();
+        let made = now - Duration::hours(hours);
+        // Discord ids start with when they were made, in milliseconds since 2015
+        let millis = made.timestamp_millis() as u64 - 1_420_070_400_000;
+        Thread {
+            id: ChannelId(millis << 22),
+            guild_id: GuildId(1),
+            parent_id: Some(GENERAL),
+            name: name.to_string(),
+            kind: ChannelType::PublicThread,
+            archived: false,
+            locked: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lock_old_threads() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[]);
+        let cache = ThreadCache::default();
+        let threads = [
+            made_at(60, "Wordle Solvers 229"),
+            // Someone a timezone behind could still be on this one
+            made_at(30, "Wordle Solvers 230"),
+            made_at(0, "Wordle Solvers 231"),
+            made_at(61, "Heardle Solvers 15"),
+        ];
+        for thread in threads.iter() {
+            platform.threads.lock().unwrap().push(thread.clone());
+            cache.insert(thread.clone());
+        }
+        let now = Utc.with_ymd_and_hms(2022, 4, 10, 12, 0, 0).unwrap();
+        lock_old_threads(&platform, &cache, GuildId(1), "Wordle", 231, now)
+            .await
+            .unwrap();
+        let locked = |platform: &FakePlatform| -> Vec<String> {
+            let threads = platform.threads.lock().unwrap();
+            threads
+                .iter()
+                .filter(|t| t.locked)
+                .map(|t| t.name.clone())
+                .collect()
+        };
+        assert_eq!(locked(&platform), vec!["Wordle Solvers 229"]);
+        assert!(cache.get(GuildId(1), "Wordle Solvers 229").unwrap().locked);
+
+        // A day later it's 230's turn
+        let later = now + Duration::days(1);
+        lock_old_threads(&platform, &cache, GuildId(1), "Wordle", 232, later)
+            .await
+            .unwrap();
+        assert_eq!(
+            locked(&platform),
+            vec!["Wordle Solvers 229", "Wordle Solvers 230"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thread_placement() {
+        let solvers = [(11, "general_solvers")];
+        // Boosted guilds get private threads right there
+        assert_eq!(
+            placed(PremiumTier::Tier2, &solvers, open()).await,
+            (ChannelType::PrivateThread, Some(GENERAL))
+        );
+        // Then the solvers channel, then the channel itself
+        assert_eq!(
+            placed(PremiumTier::Tier1, &solvers, open()).await,
+            (ChannelType::PublicThread, Some(GENERAL_SOLVERS))
+        );
+        assert_eq!(
+            placed(PremiumTier::Tier0, &[(12, "random_solvers")], open()).await,
+            (ChannelType::PublicThread, Some(GENERAL))
+        );
+        // Solvers channels can be called anything
+        let mut renamed = open();
+        renamed.solvers_channel = "spoilers".to_string();
+        assert_eq!(
+            placed(PremiumTier::Tier0, &[(11, "spoilers")], renamed).await,
+            (ChannelType::PublicThread, Some(GENERAL_SOLVERS))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gated_thread_placement() {
+        let solvers = [(11, "general_solvers")];
+        assert_eq!(
+            placed(
+                PremiumTier::Tier0,
+                &solvers,
+                placement(GateMode::Members, None)
+            )
+            .await,
+            (ChannelType::PrivateThread, Some(GENERAL))
+        );
+        assert_eq!(
+            placed(
+                PremiumTier::Tier3,
+                &solvers,
+                placement(GateMode::Role, None)
+            )
+            .await,
+            (ChannelType::PublicThread, Some(GENERAL_SOLVERS))
+        );
+        // Nowhere to hide a public one
+        assert_eq!(
+            placed(PremiumTier::Tier0, &[], placement(GateMode::Role, None)).await,
+            (ChannelType::PrivateThread, Some(GENERAL))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thread_strategy() {
+        let channels = [(11, "general_solvers"), (12, "spoilers")];
+        let strategy = |strategy: &str| placement(GateMode::Open, ThreadStrategy::parse(strategy));
+        assert_eq!(
+            placed(PremiumTier::Tier0, &channels, strategy("private")).await,
+            (ChannelType::PrivateThread, Some(GENERAL))
+        );
+        assert_eq!(
+            placed(PremiumTier::Tier3, &channels, strategy("public")).await,
+            (ChannelType::PublicThread, Some(GENERAL))
+        );
+        assert_eq!(
+            placed(PremiumTier::Tier3, &channels, strategy("public #spoilers")).await,
+            (ChannelType::PublicThread, Some(ChannelId(12)))
+        );
+        // Back to the channel itself if the named one's gone
+        assert_eq!(
+            placed(PremiumTier::Tier3, &channels, strategy("public memes")).await,
+            (ChannelType::PublicThread, Some(GENERAL))
+        );
+        // Gating by membership wins over the strategy
+        assert_eq!(
+            placed(
+                PremiumTier::Tier0,
+                &channels,
+                placement(GateMode::Members, ThreadStrategy::parse("public"))
+            )
+            .await,
+            (ChannelType::PrivateThread, Some(GENERAL))
+        );
+
+        let platform = FakePlatform::new(PremiumTier::Tier0, &channels);
+        assert_eq!(welcome(&platform, &strategy("none")).await.unwrap(), None);
+        assert!(platform.threads.lock().unwrap().is_empty());
+        assert!(platform.messages.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forum_post() {
+        let platform = FakePlatform::new(PremiumTier::Tier0, &[(12, "solvers")]);
+        let forum = placement(GateMode::Open, ThreadStrategy::parse("forum solvers"));
+        let (post, created) = welcome(&platform, &forum).await.unwrap().unwrap();
+        assert!(created);
+        assert_eq!(post.parent_id, Some(ChannelId(12)));
+        assert_eq!(
+            *platform.tags.lock().unwrap(),
+            vec![(post.id, "Wordle".to_string())]
+        );
+        // The next solver finds the same post by name
+        let (again, created) = welcome(&platform, &forum).await.unwrap().unwrap();
+        assert!(!created);
+        assert_eq!(again, post);
+        assert_eq!(platform.threads.lock().unwrap().len(), 1);
+        // The welcome starts the post off, it isn't posted twice
+        assert_eq!(
+            *platform.messages.lock().unwrap(),
+            vec![
+                (post.id, "Welcome!".to_string()),
+                (post.id, "Welcome!".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_thread_strategy_parse() {
+        assert_eq!(
+            ThreadStrategy::parse("Private"),
+            Some(ThreadStrategy::Private)
+        );
+        assert_eq!(
+            ThreadStrategy::parse("public"),
+            Some(ThreadStrategy::Public(None))
+        );
+        assert_eq!(
+            ThreadStrategy::parse("public #spoilers"),
+            Some(ThreadStrategy::Public(Some("spoilers".to_string())))
+        );
+        assert_eq!(
+            ThreadStrategy::parse("forum solvers"),
+            Some(ThreadStrategy::Forum("solvers".to_string()))
+        );
+        assert_eq!(ThreadStrategy::parse("none"), Some(ThreadStrategy::Off));
+        assert_eq!(ThreadStrategy::parse("forum"), None);
+        assert_eq!(ThreadStrategy::parse("private #spoilers"), None);
+        assert_eq!(ThreadStrategy::parse("everywhere"), None);
+        for strategy in [
+            "private",
+            "public",
+            "public #spoilers",
+            "forum #solvers",
+            "none",
+        ] {
+            let parsed = ThreadStrategy::parse(strategy).unwrap();
+            assert_eq!(String::from(parsed), strategy);
+        }
+    }
+
+    #[test]
+    fn test_thread_mode_parse() {
+        assert_eq!(ThreadMode::parse("Private"), Some(ThreadMode::Private));
+        assert_eq!(ThreadMode::parse("auto"), Some(ThreadMode::Auto));
+        assert_eq!(ThreadMode::parse("secret"), None);
+    }
+
+    #[tokio::test]
+    async fn test_explain_placement() {
+        let mut platform = FakePlatform::new(PremiumTier::Tier0, &[(11, "general_solvers")]);
+        assert_eq!(
+            explain_placement(&platform, &open()).await.unwrap(),
+            "Results posted here get public threads in <#11>, since there's a #general_solvers \
+             channel.\nI've got all the permissions I need there."
+        );
+        platform.permissions = Permissions::all() - Permissions::MANAGE_THREADS;
+        assert_eq!(
+            explain_placement(
+                &platform,
+                &placement(GateMode::Role, ThreadStrategy::parse("private"))
+            )
+            .await
+            .unwrap(),
+            "Results posted here get private threads in <#10>, since this channel's set to \
+             private threads.\nI'm missing Manage Threads there."
+        );
+        platform.permissions = Permissions::all() - Permissions::MANAGE_CHANNELS;
+        platform
+            .channels
+            .push((ChannelId(12), "solvers".to_string()));
+        assert_eq!(
+            explain_placement(
+                &platform,
+                &placement(GateMode::Open, ThreadStrategy::parse("forum solvers"))
+            )
+            .await
+            .unwrap(),
+            "Results posted here get posts in <#12>, since this channel's set to posts in \
+             #solvers.\nI'm missing Manage Channels there."
+        );
+        platform.permissions = Permissions::empty();
+        assert_eq!(
+            explain_placement(
+                &platform,
+                &placement(GateMode::Open, ThreadStrategy::parse("none"))
+            )
+            .await
+            .unwrap(),
+            "Results posted here don't get solvers threads, since threads are turned off here."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thread_refused() {
+        let mut platform = FakePlatform::new(PremiumTier::Tier2, &[]);
+        platform.refused.push(ChannelType::PrivateThread);
+        assert!(matches!(
+            welcome(&platform, &open()).await,
+            Err(BotError::MissingPermission(Some("Create Private Threads")))
+        ));
+        assert!(platform.messages.lock().unwrap().is_empty());
+    }
+}
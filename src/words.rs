@@ -1,4 +1,4 @@
-pub const VALID_WORDS: &'static [&'static str] = &[
+pub const VALID_WORDS: &[&str] = &[
     "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval", "serve",
     "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench", "abate", "feign",
     "major", "death", "fresh", "crust", "stool", "colon", "abase", "marry", "react", "batty",
@@ -232,7 +232,7 @@ pub const VALID_WORDS: &'static [&'static str] = &[
     "liege", "octal", "ombre", "payer", "sooth", "unset", "unlit", "vomit", "fanny",
 ];
 
-pub const EXTRA_WORDS: &'static [&'static str] = &[
+pub const EXTRA_WORDS: &[&str] = &[
     "aahed", "aalii", "aargh", "aarti", "abaca", "abaci", "abacs", "abaft", "abaka", "abamp",
     "aband", "abash", "abask", "abaya", "abbas", "abbed", "abbes", "abcee", "abeam", "abear",
     "abele", "abers", "abets", "abies", "abler", "ables", "ablet", "ablow", "abmho", "abohm",